@@ -0,0 +1,60 @@
+//! Generates one test per part for every `inputs/day_NN-example*.txt`, checked against the
+//! expected answers in its `.answers` sidecar; see `tests/examples.rs`.
+
+use std::{fmt::Write, fs, path::Path};
+
+const INPUT_DIR: &str = "inputs";
+
+fn main() {
+    println!("cargo::rerun-if-changed={INPUT_DIR}");
+
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let input_dir = Path::new(&manifest_dir).join(INPUT_DIR);
+
+    let mut examples = fs::read_dir(&input_dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| {
+            let name = path.file_name().unwrap().to_string_lossy();
+            name.starts_with("day_") && name.contains("-example") && name.ends_with(".txt")
+        })
+        .collect::<Vec<_>>();
+    examples.sort();
+
+    let mut tests = String::new();
+    for example in examples {
+        let name = example.file_stem().unwrap().to_string_lossy();
+        let day = name["day_".len()..]
+            .split('-')
+            .next()
+            .and_then(|day| day.parse::<u32>().ok())
+            .unwrap_or_else(|| panic!("example file has no day number: `{name}`"));
+
+        let answers = example.with_extension("answers");
+        let expected = fs::read_to_string(&answers)
+            .ok()
+            .map(|contents| contents.parse::<toml::Table>().unwrap());
+
+        for part in [1, 2] {
+            let ignore = match &expected {
+                None => "#[ignore = \"no `.answers` file\"]",
+                Some(table) if !table.contains_key(&format!("part{part}")) => {
+                    "#[ignore = \"no expected answer\"]"
+                }
+                Some(_) => "",
+            };
+            let test_name = name.replace(|c: char| !c.is_ascii_alphanumeric(), "_");
+
+            writeln!(
+                tests,
+                "#[test]{ignore}\nfn {test_name}_part{part}() {{ check({day}, {:?}, {:?}, {part}); }}",
+                example.display(),
+                answers.display(),
+            )
+            .unwrap();
+        }
+    }
+
+    let out_dir = std::env::var("OUT_DIR").unwrap();
+    fs::write(Path::new(&out_dir).join("examples.rs"), tests).unwrap();
+}
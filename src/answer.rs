@@ -0,0 +1,120 @@
+use std::fmt::Display;
+
+use serde::{Deserialize, Serialize, de, ser};
+
+/// The answer to one part of a puzzle
+///
+/// Integers compare by value regardless of which variant holds them, so `Int(5) == BigInt(5)`.
+#[derive(Clone, Debug, Default)]
+pub enum Answer {
+    Int(i64),
+    BigInt(i128),
+    Text(String),
+    #[default]
+    Unsolved,
+}
+
+impl Answer {
+    pub fn is_solved(&self) -> bool {
+        !matches!(self, Self::Unsolved)
+    }
+
+    fn as_i128(&self) -> Option<i128> {
+        match self {
+            Self::Int(n) => Some(*n as i128),
+            Self::BigInt(n) => Some(*n),
+            Self::Text(_) | Self::Unsolved => None,
+        }
+    }
+}
+
+impl PartialEq for Answer {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Text(a), Self::Text(b)) => a == b,
+            // nothing is equal to an unsolved answer, including another one
+            (Self::Unsolved, _) | (_, Self::Unsolved) => false,
+            (a, b) => a.as_i128().is_some_and(|a| Some(a) == b.as_i128()),
+        }
+    }
+}
+
+impl Display for Answer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Int(n) => write!(f, "{n}"),
+            Self::BigInt(n) => write!(f, "{n}"),
+            Self::Text(s) => write!(f, "{s}"),
+            Self::Unsolved => write!(f, "not solved"),
+        }
+    }
+}
+
+impl From<i128> for Answer {
+    fn from(n: i128) -> Self {
+        i64::try_from(n).map_or(Self::BigInt(n), Self::Int)
+    }
+}
+
+impl From<u128> for Answer {
+    fn from(n: u128) -> Self {
+        let n = i128::try_from(n).expect("answer does not fit in an `i128`");
+        n.into()
+    }
+}
+
+macro_rules! from_int {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for Answer {
+                fn from(n: $t) -> Self {
+                    (n as i128).into()
+                }
+            }
+        )*
+    };
+}
+
+from_int!(i32, u32, i64, u64, usize);
+
+impl From<String> for Answer {
+    fn from(s: String) -> Self {
+        Self::Text(s)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(s: &str) -> Self {
+        Self::Text(s.to_string())
+    }
+}
+
+/// Integers that fit in an `i64` are stored as integers; larger ones and text as strings
+impl Serialize for Answer {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Self::Int(n) => serializer.serialize_i64(*n),
+            Self::BigInt(n) => serializer.serialize_str(&n.to_string()),
+            Self::Text(s) => serializer.serialize_str(s),
+            Self::Unsolved => Err(ser::Error::custom("an unsolved answer can't be stored")),
+        }
+    }
+}
+
+/// Strings holding an integer are read back as integers, so big integers round-trip
+impl<'de> Deserialize<'de> for Answer {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Raw {
+            Int(i64),
+            Text(String),
+        }
+
+        match Raw::deserialize(deserializer) {
+            Ok(Raw::Int(n)) => Ok(Self::Int(n)),
+            Ok(Raw::Text(s)) => Ok(s.parse::<i128>().map_or(Self::Text(s), Self::from)),
+            Err(_) => Err(de::Error::custom("expected an integer or a string")),
+        }
+    }
+}
@@ -0,0 +1,152 @@
+use std::{collections::BTreeMap, fmt::Display, path::Path};
+
+use aoc_2025::answer::Answer;
+use color_eyre::eyre::{Context, Result, bail};
+use serde::{Deserialize, Serialize};
+
+use crate::{INPUT_DIR, run::DayRun};
+
+/// Known-correct answers, keyed by day (`day_01`) and then by the input's path within the inputs
+/// directory (`day_01.txt`)
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Answers(BTreeMap<String, BTreeMap<String, Expected>>);
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+struct Expected {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    part1: Option<Answer>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    part2: Option<Answer>,
+}
+
+impl Expected {
+    fn part(&self, part: usize) -> Option<&Answer> {
+        match part {
+            0 => self.part1.as_ref(),
+            1 => self.part2.as_ref(),
+            _ => unreachable!("days only have two parts"),
+        }
+    }
+
+    fn part_mut(&mut self, part: usize) -> &mut Option<Answer> {
+        match part {
+            0 => &mut self.part1,
+            1 => &mut self.part2,
+            _ => unreachable!("days only have two parts"),
+        }
+    }
+}
+
+/// How an answer compares to the expected answer for its input
+#[derive(Clone, Debug, PartialEq)]
+pub enum Status {
+    Pass,
+    Fail {
+        expected: Answer,
+    },
+    /// The part isn't solved yet, and there's no expected answer for it
+    Unsolved,
+    Unknown,
+    /// The day couldn't be run, so neither part has an answer
+    Error,
+}
+
+impl Display for Status {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Pass => write!(f, "PASS"),
+            Self::Fail { .. } => write!(f, "FAIL"),
+            Self::Unsolved => write!(f, "UNSOLVED"),
+            Self::Unknown => write!(f, "UNKNOWN"),
+            Self::Error => write!(f, "ERROR"),
+        }
+    }
+}
+
+impl Answers {
+    /// Load the answers file, treating a missing file as having no answers
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let contents = std::fs::read_to_string(path)
+            .wrap_err_with(|| format!("failed to read answers file: `{}`", path.display()))?;
+        toml::from_str(&contents)
+            .wrap_err_with(|| format!("invalid answers file: `{}`", path.display()))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let contents = toml::to_string(self)?;
+        std::fs::write(path, contents)
+            .wrap_err_with(|| format!("failed to write answers file: `{}`", path.display()))
+    }
+
+    /// Set the status of each part of `run` by comparing it against the expected answers
+    ///
+    /// Expected answers are only known for the default parameters. Runs that failed keep their
+    /// error status.
+    pub fn verify(&self, run: &mut DayRun) {
+        if run.error.is_some() {
+            return;
+        }
+
+        let expected = run
+            .input
+            .as_deref()
+            .and_then(input_key)
+            .filter(|_| run.default_params)
+            .and_then(|input| self.0.get(&day_key(run.day))?.get(&input));
+
+        for (i, part) in run.parts.iter_mut().enumerate() {
+            part.status = match expected.and_then(|e| e.part(i)) {
+                Some(expected) if *expected == part.answer => Status::Pass,
+                Some(expected) => Status::Fail {
+                    expected: expected.clone(),
+                },
+                None if !part.answer.is_solved() => Status::Unsolved,
+                None => Status::Unknown,
+            };
+        }
+    }
+
+    /// Store the solved answers from `run` as the expected answers for its input
+    pub fn record(&mut self, run: &DayRun) -> Result<()> {
+        let Some(input) = run.input.as_deref().and_then(input_key) else {
+            bail!("only answers for inputs read from a file in `{INPUT_DIR}` can be recorded");
+        };
+        if !run.default_params {
+            bail!("only answers for the default parameters can be recorded");
+        }
+
+        let expected = self
+            .0
+            .entry(day_key(run.day))
+            .or_default()
+            .entry(input)
+            .or_default();
+        for (i, part) in run.parts.iter().enumerate() {
+            if part.answer.is_solved() {
+                *expected.part_mut(i) = Some(part.answer.clone());
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn day_key(day: u32) -> String {
+    format!("day_{day:02}")
+}
+
+/// The key for the input read from `path`, if it's in the inputs directory
+///
+/// Both are canonicalized first, so the same file has the same key however its path was written.
+/// Inputs from anywhere else have no key, so they're never mistaken for the inputs of the same
+/// name that the answers are for.
+fn input_key(path: &Path) -> Option<String> {
+    let path = path.canonicalize().ok()?;
+    let input_dir = Path::new(INPUT_DIR).canonicalize().ok()?;
+    Some(path.strip_prefix(input_dir).ok()?.to_str()?.to_string())
+}
@@ -0,0 +1,126 @@
+use std::{hint::black_box, path::Path, time::Duration};
+
+use aoc_2025::{params::Params, solution::Day};
+use color_eyre::eyre::Result;
+
+use crate::{
+    run::{build_solver, time},
+    summary,
+};
+
+/// Summary statistics over a set of timing samples
+#[derive(Clone, Copy, Debug)]
+pub struct Stats {
+    min: Duration,
+    median: Duration,
+    mean: Duration,
+    p95: Duration,
+    stddev: Duration,
+}
+
+impl Stats {
+    fn from_samples(mut samples: Vec<Duration>) -> Self {
+        assert!(!samples.is_empty(), "no samples to summarise");
+        samples.sort_unstable();
+
+        let n = samples.len();
+        let median = if n.is_multiple_of(2) {
+            (samples[n / 2 - 1] + samples[n / 2]) / 2
+        } else {
+            samples[n / 2]
+        };
+
+        // nearest-rank percentile
+        let p95 = samples[(n * 95).div_ceil(100) - 1];
+
+        let mean = samples.iter().sum::<Duration>() / n as u32;
+        let variance = samples
+            .iter()
+            .map(|s| (s.as_secs_f64() - mean.as_secs_f64()).powi(2))
+            .sum::<f64>()
+            / n as f64;
+
+        Self {
+            min: samples[0],
+            median,
+            mean,
+            p95,
+            stddev: Duration::from_secs_f64(variance.sqrt()),
+        }
+    }
+
+    fn row(&self, phase: &str) -> [String; 6] {
+        [
+            phase.to_string(),
+            summary::format_duration(self.min),
+            summary::format_duration(self.median),
+            summary::format_duration(self.mean),
+            summary::format_duration(self.p95),
+            summary::format_duration(self.stddev),
+        ]
+    }
+}
+
+/// Timing statistics for each phase of a day, over repeated runs
+#[derive(Clone, Debug)]
+pub struct DayBench {
+    day: u32,
+    runs: usize,
+    warmup: usize,
+    parse: Stats,
+    part1: Stats,
+    part2: Stats,
+}
+
+impl DayBench {
+    /// Run each phase `warmup` times untimed, then `runs` times timed
+    pub fn run(
+        day: &Day,
+        params: &Params,
+        input: Option<&Path>,
+        raw_input: &str,
+        runs: usize,
+        warmup: usize,
+    ) -> Result<Self> {
+        let mut parse = Vec::with_capacity(runs);
+        let mut part1 = Vec::with_capacity(runs);
+        let mut part2 = Vec::with_capacity(runs);
+
+        for i in 0..warmup + runs {
+            let (solver, parse_time) = build_solver(day, params, input, raw_input.to_string())?;
+            let (_, part1_time) = time(|| black_box(solver.part1()));
+            let (_, part2_time) = time(|| black_box(solver.part2()));
+
+            if i >= warmup {
+                parse.push(parse_time);
+                part1.push(part1_time);
+                part2.push(part2_time);
+            }
+        }
+
+        Ok(Self {
+            day: day.number,
+            runs,
+            warmup,
+            parse: Stats::from_samples(parse),
+            part1: Stats::from_samples(part1),
+            part2: Stats::from_samples(part2),
+        })
+    }
+
+    pub fn print(&self) {
+        println!(
+            "Day {} ({} runs, {} warmup)",
+            self.day, self.runs, self.warmup
+        );
+        summary::print_table(
+            ["Phase", "Min", "Median", "Mean", "p95", "Std dev"],
+            &[
+                self.parse.row("Parse"),
+                self.part1.row("Part 1"),
+                self.part2.row("Part 2"),
+            ],
+            None,
+        );
+    }
+}
@@ -0,0 +1,5 @@
+pub mod answer;
+pub mod params;
+pub mod parse;
+pub mod solution;
+pub mod solutions;
@@ -1,32 +1,141 @@
-use std::{
-    path::PathBuf,
-    time::{Duration, Instant},
-};
+use std::{ops::RangeInclusive, path::PathBuf, str::FromStr};
 
-use clap::Parser;
-use color_eyre::eyre::{Context, Result, bail};
+use aoc_2025::{params::Params, solution::Day, solutions};
+use clap::{Parser, Subcommand};
+use color_eyre::eyre::{Context, Report, Result, bail, eyre};
 
-use crate::solution::Solution;
+use crate::{
+    answers::{Answers, Status},
+    bench::DayBench,
+    output::Format,
+    run::DayRun,
+};
 
-mod solution;
-mod solutions;
+mod answers;
+mod bench;
+mod output;
+mod run;
+mod scaffold;
+mod summary;
 
 const INPUT_DIR: &str = "inputs";
-
-macro_rules! solver {
-    ($day:ident, $raw_input:ident) => {{
-        let (solver, elapsed) = time(|| solutions::$day::with_input($raw_input));
-        (Box::new(solver) as Box<dyn Solution>, elapsed)
-    }};
-}
+const ANSWERS_PATH: &str = "answers.toml";
 
 #[derive(Debug, Clone, Parser)]
+#[command(args_conflicts_with_subcommands = true)]
 pub struct Args {
-    day: u32,
+    #[command(subcommand)]
+    command: Option<Command>,
+    /// A single day (`3`), a range of days (`1..=5`, `1..6`) or `all`
+    #[arg(required = true)]
+    days: Option<DaySelection>,
     #[clap(long)]
     example: Option<String>,
     #[clap(long)]
     example_path: Option<PathBuf>,
+    /// Benchmark each day over this many timed runs instead of solving it once
+    #[clap(long, value_name = "N", value_parser = clap::value_parser!(u32).range(1..))]
+    bench: Option<u32>,
+    /// Untimed runs to do before benchmarking
+    #[clap(long, default_value_t = 3, requires = "bench")]
+    warmup: u32,
+    /// File of expected answers to check each run against
+    #[clap(long, default_value = ANSWERS_PATH)]
+    answers: PathBuf,
+    /// Store this run's answers as the expected answers for its input
+    #[clap(long, conflicts_with = "bench")]
+    record: bool,
+    /// How to print the answers and timings
+    #[clap(long, value_enum, default_value_t, conflicts_with = "bench")]
+    format: Format,
+    /// Override one of a day's parameters (see `list`)
+    #[clap(long = "param", value_name = "NAME=VALUE", value_parser = parse_param)]
+    params: Vec<(String, usize)>,
+    /// Run one of the day's tools (see `list`) instead of solving it
+    #[clap(long, value_name = "NAME", conflicts_with_all = ["bench", "record", "format"])]
+    tool: Option<String>,
+    /// Write the tool's output to this file instead of stdout, or name the files after it for
+    /// tools that write several
+    #[clap(long, value_name = "PATH", requires = "tool")]
+    output: Option<PathBuf>,
+}
+
+fn parse_param(s: &str) -> Result<(String, usize)> {
+    let Some((name, value)) = s.split_once('=') else {
+        bail!("expected `NAME=VALUE`, found `{s}`");
+    };
+    let value = value
+        .parse()
+        .wrap_err_with(|| format!("invalid value for `{name}`: `{value}`"))?;
+    Ok((name.to_string(), value))
+}
+
+#[derive(Debug, Clone, Subcommand)]
+enum Command {
+    /// List every registered day
+    List,
+    /// Create and register a module for a new day, along with empty input files
+    New {
+        #[arg(value_parser = clap::value_parser!(u32).range(1..=25))]
+        day: u32,
+        /// The puzzle's title
+        #[arg(long)]
+        title: Option<String>,
+    },
+}
+
+#[derive(Debug, Clone)]
+enum DaySelection {
+    Single(u32),
+    Range(RangeInclusive<u32>),
+}
+
+impl DaySelection {
+    fn days(&self) -> Result<Vec<&'static Day>> {
+        match self {
+            Self::Single(day) => {
+                let Some(day) = solutions::get(*day) else {
+                    bail!("invalid day: `{day}`");
+                };
+                Ok(vec![day])
+            }
+            Self::Range(range) => {
+                let days = solutions::REGISTRY
+                    .iter()
+                    .filter(|day| range.contains(&day.number))
+                    .collect::<Vec<_>>();
+                if days.is_empty() {
+                    bail!("no solved days in `{}..={}`", range.start(), range.end());
+                }
+                Ok(days)
+            }
+        }
+    }
+}
+
+impl FromStr for DaySelection {
+    type Err = Report;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let parse_day = |d: &str| {
+            d.parse::<u32>()
+                .wrap_err_with(|| format!("invalid day: `{d}`"))
+        };
+
+        if s == "all" {
+            Ok(Self::Range(u32::MIN..=u32::MAX))
+        } else if let Some((start, end)) = s.split_once("..=") {
+            Ok(Self::Range(parse_day(start)?..=parse_day(end)?))
+        } else if let Some((start, end)) = s.split_once("..") {
+            let end = parse_day(end)?;
+            let Some(end) = end.checked_sub(1) else {
+                bail!("empty range: `{s}`");
+            };
+            Ok(Self::Range(parse_day(start)?..=end))
+        } else {
+            Ok(Self::Single(parse_day(s)?))
+        }
+    }
 }
 
 fn main() -> Result<()> {
@@ -34,50 +143,166 @@ fn main() -> Result<()> {
 
     let args = Args::parse();
 
-    let raw_input = if let Some(example_path) = args.example_path {
-        std::fs::read_to_string(&example_path).wrap_err_with(|| {
-            format!("example file does not exist: `{}`", example_path.display())
-        })?
-    } else if let Some(example) = args.example {
-        example
-    } else {
-        let input_path = PathBuf::from(INPUT_DIR).join(format!("day_{:02}.txt", args.day));
-        std::fs::read_to_string(&input_path)
-            .wrap_err_with(|| format!("example file does not exist: `{}`", input_path.display()))?
-    };
+    match &args.command {
+        Some(Command::List) => {
+            for day in solutions::REGISTRY {
+                println!("{:>2}: {}", day.number, day.title);
+                for param in day.params {
+                    println!("      {param}");
+                }
+                for tool in day.tools {
+                    println!("      --tool {}: {}", tool.name, tool.help);
+                }
+            }
+            return Ok(());
+        }
+        Some(Command::New { day, title }) => {
+            let title = title.clone().unwrap_or_else(|| format!("Day {day}"));
+            return scaffold::new_day(*day, &title);
+        }
+        None => {}
+    }
 
-    let (solver, input_build_time) = match args.day {
-        1 => solver!(Day01, raw_input),
-        2 => solver!(Day02, raw_input),
-        3 => solver!(Day03, raw_input),
-        4 => solver!(Day04, raw_input),
-        5 => solver!(Day05, raw_input),
-        6 => solver!(Day06, raw_input),
-        7 => solver!(Day07, raw_input),
-        8 => solver!(Day08, raw_input),
-        9 => solver!(Day09, raw_input),
-        10 => solver!(Day10, raw_input),
-        _ => bail!("invalid day: `{}`", args.day),
-    };
-    println!("Parsed input int {input_build_time:#?}");
+    let days = args
+        .days
+        .as_ref()
+        .expect("clap requires days without a subcommand")
+        .days()?;
+    if days.len() > 1 && (args.example.is_some() || args.example_path.is_some()) {
+        bail!("`--example` and `--example-path` can only be used with a single day");
+    }
+    for (name, _) in &args.params {
+        if !days.iter().flat_map(|d| d.params).any(|p| p.name == name) {
+            bail!("none of the selected days have a parameter named `{name}`");
+        }
+    }
+
+    if let Some(tool) = &args.tool {
+        let [day] = days.as_slice() else {
+            bail!("`--tool` can only be used with a single day");
+        };
+        let (input, raw_input) = read_input(&args, day)?;
+        return run::run_tool(
+            day,
+            &params_for(&args, day)?,
+            input.as_deref(),
+            raw_input,
+            tool,
+            args.output.as_deref(),
+        );
+    }
+
+    if let Some(runs) = args.bench {
+        for (i, day) in days.into_iter().enumerate() {
+            if i > 0 {
+                println!();
+            }
+            let (input, raw_input) = read_input(&args, day)?;
+            DayBench::run(
+                day,
+                &params_for(&args, day)?,
+                input.as_deref(),
+                &raw_input,
+                runs as usize,
+                args.warmup as usize,
+            )?
+            .print();
+        }
+        return Ok(());
+    }
+
+    let mut answers = Answers::load(&args.answers)?;
 
-    let (part1, elapsed) = time(|| solver.part1());
-    println!("Day {} Part 1:", args.day);
-    println!("{part1}");
-    println!("Took {elapsed:#?}");
+    let mut runs = Vec::with_capacity(days.len());
+    for day in days {
+        let run = read_input(&args, day).and_then(|(input, raw_input)| {
+            DayRun::run(day, &params_for(&args, day)?, input, raw_input)
+        });
+        let mut run = run.unwrap_or_else(|e| DayRun::failed(day, e));
+        answers.verify(&mut run);
+        runs.push(run);
+    }
 
-    println!();
+    // a single day has no table to show the error in
+    if let [run] = runs.as_mut_slice()
+        && let Some(error) = run.error.take()
+    {
+        return Err(error);
+    }
 
-    let (part2, elapsed) = time(|| solver.part2());
-    println!("Day {} Part 2:", args.day);
-    println!("{part2}");
-    println!("Took {elapsed:#?}");
+    match (args.format, runs.as_slice()) {
+        (Format::Text, [run]) => print_run(run),
+        (Format::Text, _) => summary::print_summary(&runs),
+        (format, _) => output::print_records(&runs, format)?,
+    }
+
+    if args.record {
+        for run in runs.iter().filter(|run| run.error.is_none()) {
+            answers.record(run)?;
+        }
+        answers.save(&args.answers)?;
+        eprintln!();
+        eprintln!("Recorded answers in `{}`", args.answers.display());
+    }
+
+    let errors = runs.iter().filter(|run| run.error.is_some()).count();
+    if errors > 0 {
+        bail!("{errors} day(s) could not be run");
+    }
+    if args.record {
+        return Ok(());
+    }
+
+    let failures = runs
+        .iter()
+        .flat_map(|run| &run.parts)
+        .filter(|part| matches!(part.status, Status::Fail { .. }))
+        .count();
+    if failures > 0 {
+        bail!("{failures} answer(s) did not match the expected answer");
+    }
 
     Ok(())
 }
 
-fn time<T, F: FnOnce() -> T>(f: F) -> (T, Duration) {
-    let start = Instant::now();
-    let input = f();
-    (input, start.elapsed())
+/// The parameters for `day`, with any overrides from `--param` applied
+fn params_for(args: &Args, day: &Day) -> Result<Params> {
+    Params::with_overrides(day.params, &args.params).map_err(|e| eyre!("day {}: {e}", day.number))
+}
+
+/// Read the input for `day`, along with the path it was read from if it came from a file
+fn read_input(args: &Args, day: &Day) -> Result<(Option<PathBuf>, String)> {
+    let input = if let Some(example_path) = &args.example_path {
+        let raw_input = std::fs::read_to_string(example_path).wrap_err_with(|| {
+            format!("example file does not exist: `{}`", example_path.display())
+        })?;
+        (Some(example_path.clone()), raw_input)
+    } else if let Some(example) = &args.example {
+        (None, example.clone())
+    } else {
+        let input_path = PathBuf::from(INPUT_DIR).join(format!("day_{:02}.txt", day.number));
+        let raw_input = std::fs::read_to_string(&input_path)
+            .wrap_err_with(|| format!("example file does not exist: `{}`", input_path.display()))?;
+        (Some(input_path), raw_input)
+    };
+
+    Ok(input)
+}
+
+fn print_run(run: &DayRun) {
+    println!("Parsed input int {:#?}", run.parse_time);
+
+    for (i, part) in run.parts.iter().enumerate() {
+        if i > 0 {
+            println!();
+        }
+
+        println!("Day {} Part {}:", run.day, i + 1);
+        println!("{}", part.answer);
+        println!("{}", part.status);
+        if let Status::Fail { expected } = &part.status {
+            summary::print_diff(expected, &part.answer);
+        }
+        println!("Took {:#?}", part.time);
+    }
 }
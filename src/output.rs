@@ -0,0 +1,99 @@
+use clap::ValueEnum;
+use color_eyre::eyre::Result;
+use serde::Serialize;
+
+use crate::{answers::Status, run::DayRun};
+
+/// How to print the results of a run
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum Format {
+    /// Human-readable output, with a summary table when running more than one day
+    #[default]
+    Text,
+    /// A JSON array with one record per day and part
+    Json,
+    /// A header row followed by one row per day and part
+    Csv,
+}
+
+/// The result of one part of one day, flattened for machine consumption
+#[derive(Debug, Serialize)]
+struct Record {
+    day: u32,
+    part: usize,
+    input: Option<String>,
+    answer: Option<String>,
+    duration_ns: u128,
+    parse_ns: u128,
+    status: String,
+    expected: Option<String>,
+    /// Why the day couldn't be run
+    error: Option<String>,
+}
+
+const CSV_HEADER: &str = "day,part,input,answer,duration_ns,parse_ns,status,expected,error";
+
+impl Record {
+    fn from_runs(runs: &[DayRun]) -> impl Iterator<Item = Self> {
+        runs.iter().flat_map(|run| {
+            run.parts.iter().enumerate().map(|(i, part)| Record {
+                day: run.day,
+                part: i + 1,
+                input: run.input.as_ref().map(|p| p.display().to_string()),
+                answer: part.answer.is_solved().then(|| part.answer.to_string()),
+                duration_ns: part.time.as_nanos(),
+                parse_ns: run.parse_time.as_nanos(),
+                status: part.status.to_string(),
+                expected: match &part.status {
+                    Status::Fail { expected } => Some(expected.to_string()),
+                    Status::Pass => Some(part.answer.to_string()),
+                    Status::Unsolved | Status::Unknown | Status::Error => None,
+                },
+                error: run.error.as_ref().map(|e| format!("{e:#}")),
+            })
+        })
+    }
+
+    fn to_csv(&self) -> String {
+        [
+            self.day.to_string(),
+            self.part.to_string(),
+            csv_field(self.input.as_deref().unwrap_or_default()),
+            csv_field(self.answer.as_deref().unwrap_or_default()),
+            self.duration_ns.to_string(),
+            self.parse_ns.to_string(),
+            self.status.clone(),
+            csv_field(self.expected.as_deref().unwrap_or_default()),
+            csv_field(self.error.as_deref().unwrap_or_default()),
+        ]
+        .join(",")
+    }
+}
+
+/// Print the runs as JSON or CSV
+pub fn print_records(runs: &[DayRun], format: Format) -> Result<()> {
+    match format {
+        Format::Text => unreachable!("text output is printed by `main`"),
+        Format::Json => {
+            let records = Record::from_runs(runs).collect::<Vec<_>>();
+            println!("{}", serde_json::to_string_pretty(&records)?);
+        }
+        Format::Csv => {
+            println!("{CSV_HEADER}");
+            for record in Record::from_runs(runs) {
+                println!("{}", record.to_csv());
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Quote a field if it contains anything that would break the row apart
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
@@ -0,0 +1,94 @@
+use std::{collections::BTreeMap, fmt::Display, ops::RangeInclusive};
+
+/// A tunable value that a solution reads at runtime, such as a puzzle constant that differs
+/// between the example and the real input
+#[derive(Clone, Debug)]
+pub struct ParamSpec {
+    pub name: &'static str,
+    pub default: usize,
+    pub help: &'static str,
+    pub range: RangeInclusive<usize>,
+}
+
+impl ParamSpec {
+    pub const fn new(name: &'static str, default: usize, help: &'static str) -> Self {
+        Self {
+            name,
+            default,
+            help,
+            range: 0..=usize::MAX,
+        }
+    }
+
+    /// Restrict the values the parameter can be set to
+    pub const fn range(mut self, range: RangeInclusive<usize>) -> Self {
+        self.range = range;
+        self
+    }
+}
+
+impl Display for ParamSpec {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}={}: {}", self.name, self.default, self.help)
+    }
+}
+
+/// The value of each of a solution's parameters
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Params {
+    values: BTreeMap<&'static str, usize>,
+    is_default: bool,
+}
+
+impl Params {
+    /// The default value of each parameter in `specs`
+    pub fn defaults(specs: &[ParamSpec]) -> Self {
+        let values = specs.iter().map(|spec| (spec.name, spec.default)).collect();
+        Self {
+            values,
+            is_default: true,
+        }
+    }
+
+    /// The parameters in `specs`, with any of `overrides` that name one of them applied
+    pub fn with_overrides(
+        specs: &[ParamSpec],
+        overrides: &[(String, usize)],
+    ) -> Result<Self, String> {
+        let mut params = Self::defaults(specs);
+
+        for (name, value) in overrides {
+            let Some(spec) = specs.iter().find(|spec| spec.name == name) else {
+                continue;
+            };
+            if !spec.range.contains(value) {
+                return Err(format!(
+                    "`{name}` must be in {}..={}, got {value}",
+                    spec.range.start(),
+                    spec.range.end()
+                ));
+            }
+
+            params.values.insert(spec.name, *value);
+        }
+
+        params.is_default = specs
+            .iter()
+            .all(|spec| params.values[spec.name] == spec.default);
+
+        Ok(params)
+    }
+
+    /// The value of the parameter `name`, which must have been declared by the solution
+    pub fn get(&self, name: &str) -> usize {
+        *self
+            .values
+            .get(name)
+            .unwrap_or_else(|| panic!("undeclared parameter: `{name}`"))
+    }
+
+    /// Whether every parameter has its default value
+    pub fn is_default(&self) -> bool {
+        self.is_default
+    }
+}
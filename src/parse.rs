@@ -0,0 +1,103 @@
+use std::{error::Error, fmt::Display, str::FromStr};
+
+/// An error in a puzzle input, located by line and column
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseError {
+    message: String,
+    /// 1-based line number
+    line: usize,
+    /// 1-based column, counted in chars
+    column: usize,
+    /// How many chars the offending span covers, at least 1
+    len: usize,
+    /// The full text of the offending line
+    snippet: String,
+}
+
+impl ParseError {
+    /// An error in `span`, which should be a slice of `line`; anything else is treated as covering
+    /// the whole line
+    ///
+    /// The error is on line 1 until it's moved with [`Self::offset_lines`].
+    pub fn new(line: &str, span: &str, message: impl Into<String>) -> Self {
+        let (offset, span) = match (span.as_ptr() as usize).checked_sub(line.as_ptr() as usize) {
+            Some(offset) if offset + span.len() <= line.len() => (offset, span),
+            _ => (0, line),
+        };
+
+        Self {
+            message: message.into(),
+            line: 1,
+            column: line[..offset].chars().count() + 1,
+            len: span.chars().count().max(1),
+            snippet: line.to_string(),
+        }
+    }
+
+    /// An error covering all of `line`
+    pub fn line(line: &str, message: impl Into<String>) -> Self {
+        Self::new(line, line, message)
+    }
+
+    /// An error just past the end of `input`, for input that stops early
+    pub fn end_of_input(input: &str, message: impl Into<String>) -> Self {
+        let input = input.trim_end();
+        let lines = input.lines().count();
+        let last = input.lines().last().unwrap_or_default();
+        Self::new(last, &last[last.len()..], message).offset_lines(lines.saturating_sub(1))
+    }
+
+    /// Move the error down by `lines`, for when it was built from a line other than the first
+    pub fn offset_lines(mut self, lines: usize) -> Self {
+        self.line += lines;
+        self
+    }
+
+    /// The offending line, with the error's span underlined
+    pub fn excerpt(&self) -> String {
+        let number = self.line.to_string();
+        let gutter = " ".repeat(number.len());
+        let padding = " ".repeat(self.column - 1);
+        let carets = "^".repeat(self.len);
+
+        format!(
+            "{gutter} |\n{number} | {}\n{gutter} | {padding}{carets} {}",
+            self.snippet, self.message
+        )
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: {}",
+            self.line, self.column, self.message
+        )
+    }
+}
+
+impl Error for ParseError {}
+
+/// Parse each line of `input` with `f`, ignoring any trailing blank lines
+pub fn parse_lines<T>(
+    input: &str,
+    mut f: impl FnMut(&str) -> Result<T, ParseError>,
+) -> Result<Vec<T>, ParseError> {
+    input
+        .trim_end()
+        .lines()
+        .enumerate()
+        .map(|(i, line)| f(line).map_err(|e| e.offset_lines(i)))
+        .collect()
+}
+
+/// Parse `span`, a slice of `line`, as a number
+pub fn parse_number<T>(line: &str, span: &str) -> Result<T, ParseError>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    span.parse()
+        .map_err(|e| ParseError::new(line, span, format!("invalid number `{span}`: {e}")))
+}
@@ -0,0 +1,156 @@
+use std::{
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+use aoc_2025::{
+    answer::Answer,
+    params::Params,
+    solution::{Day, Solution, ToolOutput},
+};
+use color_eyre::{
+    Section, SectionExt,
+    eyre::{Context, Report, Result, bail},
+};
+
+use crate::answers::Status;
+
+/// The answers and timings from running a single day
+#[derive(Debug)]
+pub struct DayRun {
+    pub day: u32,
+    /// Where the input was read from, if it came from a file
+    pub input: Option<PathBuf>,
+    /// Whether the day ran with its default parameters, which expected answers are for
+    pub default_params: bool,
+    pub parse_time: Duration,
+    pub parts: [PartRun; 2],
+    /// Why the day couldn't be run, in which case neither part has an answer
+    pub error: Option<Report>,
+}
+
+/// The answer to one part of a day, and how it compares to the expected answer
+#[derive(Clone, Debug)]
+pub struct PartRun {
+    pub answer: Answer,
+    pub time: Duration,
+    pub status: Status,
+}
+
+impl PartRun {
+    fn new((answer, time): (Answer, Duration)) -> Self {
+        Self {
+            answer,
+            time,
+            status: Status::Unknown,
+        }
+    }
+}
+
+impl DayRun {
+    pub fn run(
+        day: &Day,
+        params: &Params,
+        input: Option<PathBuf>,
+        raw_input: String,
+    ) -> Result<Self> {
+        let (solver, parse_time) = build_solver(day, params, input.as_deref(), raw_input)?;
+        let part1 = PartRun::new(time(|| solver.part1()));
+        let part2 = PartRun::new(time(|| solver.part2()));
+
+        Ok(Self {
+            day: day.number,
+            input,
+            default_params: params.is_default(),
+            parse_time,
+            parts: [part1, part2],
+            error: None,
+        })
+    }
+
+    /// A run of `day` that stopped with `error` before either part was solved, like when its input
+    /// couldn't be read or parsed
+    pub fn failed(day: &Day, error: Report) -> Self {
+        let part = PartRun {
+            answer: Answer::Unsolved,
+            time: Duration::ZERO,
+            status: Status::Error,
+        };
+
+        Self {
+            day: day.number,
+            input: None,
+            default_params: true,
+            parse_time: Duration::ZERO,
+            parts: [part.clone(), part],
+            error: Some(error),
+        }
+    }
+
+    pub fn total_time(&self) -> Duration {
+        self.parse_time + self.parts.iter().map(|p| p.time).sum::<Duration>()
+    }
+}
+
+/// Build the solution for `day`, timing how long parsing its input takes
+///
+/// A parse error is reported along with an excerpt of the input, and `input`'s path if it came
+/// from a file.
+pub fn build_solver(
+    day: &Day,
+    params: &Params,
+    input: Option<&Path>,
+    raw_input: String,
+) -> Result<(Box<dyn Solution>, Duration)> {
+    let (solver, elapsed) = time(|| day.solver(raw_input, params));
+
+    let solver = solver.map_err(|e| {
+        let excerpt = e.excerpt();
+        let source = match input {
+            Some(path) => format!("`{}`", path.display()),
+            None => "the input".to_string(),
+        };
+        Report::new(e)
+            .wrap_err(format!("failed to parse {source} for day {}", day.number))
+            .section(excerpt.header("Input:"))
+    })?;
+
+    Ok((solver, elapsed))
+}
+
+/// Run the tool `name` on `day`'s solution, writing its output to `output`, or stdout if there
+/// isn't one
+pub fn run_tool(
+    day: &Day,
+    params: &Params,
+    input: Option<&Path>,
+    raw_input: String,
+    name: &str,
+    output: Option<&Path>,
+) -> Result<()> {
+    if day.tool(name).is_none() {
+        let tools = day.tools.iter().map(|tool| tool.name).collect::<Vec<_>>();
+        if tools.is_empty() {
+            bail!("day {} has no tools", day.number);
+        }
+        bail!(
+            "day {} has no tool named `{name}`; expected one of: {}",
+            day.number,
+            tools.join(", ")
+        );
+    }
+
+    let (solver, _) = build_solver(day, params, input, raw_input)?;
+
+    let mut output = ToolOutput::new(output.map(Path::to_path_buf));
+    solver
+        .run_tool(name, &mut output)
+        .and_then(|()| output.flush())
+        .wrap_err_with(|| format!("failed to run tool `{name}` for day {}", day.number))
+}
+
+pub fn time<T, F: FnOnce() -> T>(f: F) -> (T, Duration) {
+    let start = Instant::now();
+    let input = f();
+    (input, start.elapsed())
+}
@@ -0,0 +1,109 @@
+use std::path::{Path, PathBuf};
+
+use aoc_2025::solutions;
+use color_eyre::eyre::{Context, Result, bail};
+
+use crate::INPUT_DIR;
+
+const SOLUTIONS_DIR: &str = "src/solutions";
+const REGISTRY_START: &str = "registry! {";
+
+const TEMPLATE: &str = r#"use crate::{
+    params::Params,
+    parse::{self, ParseError},
+    solution::{Day, Solution},
+};
+
+pub const DAY: Day = Day::new::<DayNN>(NUMBER, TITLE);
+
+#[derive(Debug)]
+pub struct DayNN {
+    #[allow(unused)]
+    lines: Vec<String>,
+}
+
+impl Solution for DayNN {
+    fn with_input(input: String, _params: &Params) -> Result<Self, ParseError> {
+        let lines = parse::parse_lines(&input, |line| Ok(line.to_string()))?;
+        Ok(Self { lines })
+    }
+}
+"#;
+
+/// Create the module and empty input files for `day`, and register the module
+///
+/// Nothing is written if any of the files already exist or the day is already registered.
+pub fn new_day(day: u32, title: &str) -> Result<()> {
+    if solutions::get(day).is_some() {
+        bail!("day {day} is already registered");
+    }
+
+    let module = format!("day_{day:02}");
+    let module_path = Path::new(SOLUTIONS_DIR).join(format!("{module}.rs"));
+    let input_path = PathBuf::from(INPUT_DIR).join(format!("{module}.txt"));
+    let example_path = PathBuf::from(INPUT_DIR).join(format!("{module}-example.txt"));
+
+    for path in [&module_path, &input_path, &example_path] {
+        if path.exists() {
+            bail!("refusing to overwrite `{}`", path.display());
+        }
+    }
+
+    let registry_path = Path::new(SOLUTIONS_DIR).join("mod.rs");
+    let registry = std::fs::read_to_string(&registry_path)
+        .wrap_err_with(|| format!("failed to read `{}`", registry_path.display()))?;
+    let registry =
+        register(&registry, &module).wrap_err_with(|| format!("failed to register `{module}`"))?;
+
+    let source = TEMPLATE
+        .replace("DayNN", &format!("Day{day:02}"))
+        .replace("NUMBER", &day.to_string())
+        .replace("TITLE", &format!("{title:?}"));
+
+    for (path, contents) in [
+        (&module_path, source.as_str()),
+        (&input_path, ""),
+        (&example_path, ""),
+        (&registry_path, registry.as_str()),
+    ] {
+        std::fs::write(path, contents)
+            .wrap_err_with(|| format!("failed to write `{}`", path.display()))?;
+        println!("Wrote `{}`", path.display());
+    }
+
+    Ok(())
+}
+
+/// Add `module` to the `registry!` invocation in `source`, keeping the modules in order
+fn register(source: &str, module: &str) -> Result<String> {
+    let Some(start) = source.find(REGISTRY_START) else {
+        bail!("no `{REGISTRY_START}` found");
+    };
+    let body_start = start + REGISTRY_START.len();
+    let Some(body_len) = source[body_start..].find('}') else {
+        bail!("unterminated `{REGISTRY_START}`");
+    };
+    let body = &source[body_start..body_start + body_len];
+
+    let mut modules = body
+        .split(',')
+        .map(str::trim)
+        .filter(|m| !m.is_empty())
+        .collect::<Vec<_>>();
+    if modules.contains(&module) {
+        bail!("`{module}` is already registered");
+    }
+    modules.push(module);
+    modules.sort_unstable();
+
+    let body = modules
+        .iter()
+        .map(|m| format!("\n    {m},"))
+        .collect::<String>();
+
+    Ok(format!(
+        "{}{body}\n{}",
+        &source[..body_start],
+        &source[body_start + body_len..]
+    ))
+}
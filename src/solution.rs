@@ -1,15 +1,179 @@
-use std::fmt::Debug;
+use std::{
+    fmt::Debug,
+    fs::File,
+    io::{self, BufWriter, IsTerminal, Write},
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    answer::Answer,
+    params::{ParamSpec, Params},
+    parse::ParseError,
+};
 
 pub trait Solution: Debug {
-    fn with_input(input: String) -> Self
+    /// Parse the input, reading any parameters the solution's [`Day`] declared from `params`
+    fn with_input(input: String, params: &Params) -> Result<Self, ParseError>
     where
         Self: Sized;
 
-    fn part1(&self) -> String {
-        "part 1 not solved".to_string()
+    fn part1(&self) -> Answer {
+        Answer::Unsolved
+    }
+
+    fn part2(&self) -> Answer {
+        Answer::Unsolved
+    }
+
+    /// Run the tool `name`, one of those the solution's [`Day`] declared, writing what it
+    /// produces to `output`
+    fn run_tool(&self, name: &str, output: &mut ToolOutput) -> io::Result<()> {
+        let _ = output;
+        Err(Tool::unknown(name))
+    }
+}
+
+/// An extra command a solution offers alongside its parts, such as explaining how it reached an
+/// answer
+#[derive(Clone, Copy, Debug)]
+pub struct Tool {
+    pub name: &'static str,
+    pub help: &'static str,
+}
+
+impl Tool {
+    pub const fn new(name: &'static str, help: &'static str) -> Self {
+        Self { name, help }
+    }
+
+    /// The error for running a tool named `name` that a solution doesn't offer
+    pub fn unknown(name: &str) -> io::Error {
+        io::Error::new(
+            io::ErrorKind::Unsupported,
+            format!("no tool named `{name}`"),
+        )
+    }
+}
+
+/// Where a tool writes what it produces: the file given with `--output`, or stdout if there isn't
+/// one
+pub struct ToolOutput {
+    path: Option<PathBuf>,
+    out: Option<Box<dyn Write>>,
+}
+
+impl ToolOutput {
+    pub fn new(path: Option<PathBuf>) -> Self {
+        Self { path, out: None }
     }
 
-    fn part2(&self) -> String {
-        "part 2 not solved".to_string()
+    /// The output file or stdout, which is only opened the first time it's asked for, so tools
+    /// that write [numbered](Self::numbered) files don't also create an empty one
+    pub fn out(&mut self) -> io::Result<&mut dyn Write> {
+        if self.out.is_none() {
+            let out: Box<dyn Write> = match &self.path {
+                Some(path) => Box::new(create(path)?),
+                None => Box::new(io::stdout().lock()),
+            };
+            self.out = Some(out);
+        }
+        Ok(self.out.as_mut().unwrap().as_mut())
     }
+
+    /// Whether the output is a terminal someone's watching, rather than a file or a pipe
+    pub fn is_terminal(&self) -> bool {
+        self.path.is_none() && io::stdout().is_terminal()
+    }
+
+    /// A new file alongside the output file, for tools that write several: file 3 for
+    /// `frames/erosion.pgm` is `frames/erosion-003.pgm`
+    pub fn numbered(&self, n: usize) -> io::Result<BufWriter<File>> {
+        let Some(path) = &self.path else {
+            return Err(io::Error::other(
+                "this tool writes several files, so needs an `--output` path to name them after",
+            ));
+        };
+        create(&numbered_path(path, n))
+    }
+
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.out.as_mut().map_or(Ok(()), |out| out.flush())
+    }
+}
+
+fn create(path: &Path) -> io::Result<BufWriter<File>> {
+    File::create(path).map(BufWriter::new).map_err(|e| {
+        io::Error::new(
+            e.kind(),
+            format!("failed to create `{}`: {e}", path.display()),
+        )
+    })
+}
+
+/// `path` with `-n` added to the end of its file stem
+fn numbered_path(path: &Path, n: usize) -> PathBuf {
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    let name = match path.extension() {
+        Some(extension) => format!("{stem}-{n:03}.{}", extension.to_string_lossy()),
+        None => format!("{stem}-{n:03}"),
+    };
+    path.with_file_name(name)
+}
+
+/// A day's entry in the registry: which day it is, the puzzle's title and how to build its
+/// solution
+///
+/// Each day module declares its entry as `pub const DAY: Day`, which the `registry!` in
+/// `solutions/mod.rs` collects.
+#[derive(Clone, Copy, Debug)]
+pub struct Day {
+    pub number: u32,
+    pub title: &'static str,
+    /// Parameters the solution reads in `with_input`
+    pub params: &'static [ParamSpec],
+    /// Tools the solution runs in `run_tool`
+    pub tools: &'static [Tool],
+    build: Builder,
+}
+
+type Builder = fn(String, &Params) -> Result<Box<dyn Solution>, ParseError>;
+
+impl Day {
+    pub const fn new<S: Solution + 'static>(number: u32, title: &'static str) -> Self {
+        Self {
+            number,
+            title,
+            params: &[],
+            tools: &[],
+            build: build::<S>,
+        }
+    }
+
+    /// Declare the parameters the solution reads
+    pub const fn with_params(mut self, params: &'static [ParamSpec]) -> Self {
+        self.params = params;
+        self
+    }
+
+    /// Declare the tools the solution offers
+    pub const fn with_tools(mut self, tools: &'static [Tool]) -> Self {
+        self.tools = tools;
+        self
+    }
+
+    /// Look up one of the solution's tools by name
+    pub fn tool(&self, name: &str) -> Option<&'static Tool> {
+        self.tools.iter().find(|tool| tool.name == name)
+    }
+
+    pub fn solver(&self, input: String, params: &Params) -> Result<Box<dyn Solution>, ParseError> {
+        (self.build)(input, params)
+    }
+}
+
+fn build<S: Solution + 'static>(
+    input: String,
+    params: &Params,
+) -> Result<Box<dyn Solution>, ParseError> {
+    Ok(Box::new(S::with_input(input, params)?))
 }
@@ -1,33 +1,55 @@
 use std::ops::Add;
 
-use crate::solution::Solution;
+use crate::{
+    answer::Answer,
+    params::{ParamSpec, Params},
+    parse::{self, ParseError},
+    solution::{Day, Solution},
+};
 
 #[derive(Clone, Copy, Debug)]
 struct Instr(i32);
 
 impl Instr {
-    fn parse(line: &str) -> Self {
-        let (dir, dist) = line.split_at(1);
+    fn parse(line: &str) -> Result<Self, ParseError> {
+        let Some((dir, dist)) = line.split_at_checked(1) else {
+            return Err(ParseError::line(line, "expected a direction and distance"));
+        };
 
         let dir = match dir {
             "L" => -1,
             "R" => 1,
-            other => panic!("invalid direction: {other}"),
+            other => {
+                return Err(ParseError::new(
+                    line,
+                    dir,
+                    format!("invalid direction: `{other}`"),
+                ));
+            }
         };
 
-        let dist = dist.parse::<i32>().expect("invalid distance");
+        let dist = parse::parse_number::<i32>(line, dist)?;
 
-        Self(dist * dir)
+        Ok(Self(dist * dir))
     }
 }
 
 #[derive(Clone, Copy, Debug)]
 struct Safe {
     pos: i32,
+    size: i32,
     crossed_zero_times: usize,
 }
 
 impl Safe {
+    fn new(start: i32, size: i32) -> Self {
+        Self {
+            pos: start.rem_euclid(size),
+            size,
+            crossed_zero_times: 0,
+        }
+    }
+
     fn iter<I: Iterator<Item = Instr>>(self, instrs: I) -> SafeIter<I> {
         SafeIter { safe: self, instrs }
     }
@@ -41,39 +63,32 @@ impl Safe {
     }
 }
 
-impl Default for Safe {
-    fn default() -> Self {
-        Self {
-            pos: 50,
-            crossed_zero_times: 0,
-        }
-    }
-}
-
 impl Add<Instr> for Safe {
     type Output = Self;
 
     fn add(self, Instr(dist): Instr) -> Self::Output {
+        let size = self.size;
         let mut pos = self.pos + dist;
 
-        let crossed_zero_times = if self.pos == 0 && (-99..0).contains(&pos) {
+        let crossed_zero_times = if self.pos == 0 && (1 - size..0).contains(&pos) {
             0
         } else if pos.is_negative() {
-            let czt = (pos / 100).unsigned_abs() as usize;
-            if pos % 100 == 0 { czt } else { czt + 1 }
+            let czt = (pos / size).unsigned_abs() as usize;
+            if pos % size == 0 { czt } else { czt + 1 }
         } else if pos.is_positive() {
-            (pos / 100) as usize
+            (pos / size) as usize
         } else {
             1
         };
 
-        pos %= 100;
+        pos %= size;
         if pos.is_negative() {
-            pos += 100;
+            pos += size;
         }
 
         Self {
             pos,
+            size,
             crossed_zero_times,
         }
     }
@@ -94,30 +109,47 @@ impl<I: Iterator<Item = Instr>> Iterator for SafeIter<I> {
     }
 }
 
+pub const DAY: Day = Day::new::<Day01>(1, "Secret Entrance").with_params(&[
+    ParamSpec::new("dial_start", 50, "the number the dial starts at").range(0..=1_000_000),
+    ParamSpec::new("dial_size", 100, "how many numbers are on the dial").range(1..=1_000_000),
+]);
+
 #[derive(Debug)]
 pub struct Day01 {
     instructions: Vec<Instr>,
+    dial_start: i32,
+    dial_size: i32,
+}
+
+impl Day01 {
+    fn safe(&self) -> Safe {
+        Safe::new(self.dial_start, self.dial_size)
+    }
 }
 
 impl Solution for Day01 {
-    fn with_input(input: String) -> Self {
-        let instructions = input.lines().map(Instr::parse).collect();
-        Self { instructions }
+    fn with_input(input: String, params: &Params) -> Result<Self, ParseError> {
+        let instructions = parse::parse_lines(&input, Instr::parse)?;
+        Ok(Self {
+            instructions,
+            dial_start: params.get("dial_start") as i32,
+            dial_size: params.get("dial_size") as i32,
+        })
     }
 
-    fn part1(&self) -> String {
-        Safe::default()
+    fn part1(&self) -> Answer {
+        self.safe()
             .iter(self.instructions.iter().copied())
             .filter(Safe::is_zero)
             .count()
-            .to_string()
+            .into()
     }
 
-    fn part2(&self) -> String {
-        Safe::default()
+    fn part2(&self) -> Answer {
+        self.safe()
             .iter(self.instructions.iter().copied())
             .map(Safe::crossed_zero_times)
             .sum::<usize>()
-            .to_string()
+            .into()
     }
 }
@@ -1,90 +1,141 @@
-use std::io::Write;
-
-use itertools::Itertools;
-
-use crate::solution::Solution;
+use crate::{
+    answer::Answer,
+    params::Params,
+    parse::{self, ParseError},
+    solution::{Day, Solution},
+};
 
 #[derive(Clone, Copy, Debug)]
 struct IdRange(u64, u64);
 
 impl IdRange {
-    fn parse(range: &str) -> Self {
-        let (start, end) = range.split_once('-').unwrap();
-        Self(start.parse().unwrap(), end.parse().unwrap())
+    /// Parse `range`, a slice of `line`
+    fn parse(line: &str, range: &str) -> Result<Self, ParseError> {
+        let Some((start, end)) = range.split_once('-') else {
+            return Err(ParseError::new(
+                line,
+                range,
+                format!("expected a range like `11-22`, found `{range}`"),
+            ));
+        };
+        Ok(Self(
+            parse::parse_number(line, start)?,
+            parse::parse_number(line, end)?,
+        ))
     }
 
-    fn ids(&self) -> impl Iterator<Item = Id> {
-        (self.0..=self.1).map(Id)
-    }
-}
-
-#[derive(Clone, Copy, Debug)]
-struct Id(u64);
-
-impl Id {
-    fn contains_double_repeat(&self, buf: &mut Vec<u8>) -> bool {
-        buf.clear();
-        write!(buf, "{}", self.0).unwrap();
-
-        if !buf.len().is_multiple_of(2) {
-            return false;
+    /// The sum of the IDs in the range that are `len` digits long and made of a block of
+    /// `period` digits repeated, where `period` divides `len`
+    ///
+    /// Those IDs are exactly the multiples of a repunit-like factor (`1001` for a block of 3
+    /// repeated twice, `10101` for a block of 2 repeated three times) by a block with no leading
+    /// zero, so they form an arithmetic series.
+    fn sum_repeats(&self, len: u32, period: u32) -> u128 {
+        let factor = (0..len / period)
+            .map(|i| 10u128.pow(i * period))
+            .sum::<u128>();
+
+        let start = u128::from(self.0).max(10u128.pow(len - 1));
+        let end = u128::from(self.1).min(10u128.pow(len) - 1);
+        let first = start.div_ceil(factor).max(10u128.pow(period - 1));
+        let last = (end / factor).min(10u128.pow(period) - 1);
+        if first > last {
+            return 0;
         }
 
-        let (left, right) = buf.split_at(buf.len() / 2);
-
-        left == right
+        factor * (first + last) * (last - first + 1) / 2
     }
 
-    fn contains_any_repeat(&self, buf: &mut Vec<u8>) -> bool {
-        if self.contains_double_repeat(buf) {
-            return true;
-        }
-
-        // buffer has already been cleared and written
+    /// The sum of the IDs in the range made of a block of digits repeated exactly twice
+    fn sum_double_repeats(&self) -> u128 {
+        (2..=MAX_DIGITS)
+            .step_by(2)
+            .map(|len| self.sum_repeats(len, len / 2))
+            .sum()
+    }
 
-        for num_parts in 2..=buf.len() {
-            if !buf.len().is_multiple_of(num_parts) {
-                continue;
-            }
+    /// The sum of the IDs in the range made of a block of digits repeated at least twice
+    ///
+    /// An ID of `len` digits that repeats with some period also repeats with every multiple of it
+    /// that divides `len`, so every repeating ID repeats with a period of `len / p` for some prime
+    /// `p`. Those sets overlap, where an ID repeats with the period for several primes, so they're
+    /// combined by inclusion–exclusion: an ID that repeats with `len / p` and `len / q` repeats
+    /// with `len / pq`.
+    fn sum_any_repeats(&self) -> u128 {
+        (2..=MAX_DIGITS)
+            .map(|len| {
+                let primes = prime_factors(len);
+                let (mut added, mut removed) = (0, 0);
+                for subset in 1..1u32 << primes.len() {
+                    let product = primes
+                        .iter()
+                        .enumerate()
+                        .filter(|(i, _)| subset >> i & 1 == 1)
+                        .map(|(_, p)| p)
+                        .product::<u32>();
+                    let sum = self.sum_repeats(len, len / product);
+                    if subset.count_ones() % 2 == 1 {
+                        added += sum;
+                    } else {
+                        removed += sum;
+                    }
+                }
+                added - removed
+            })
+            .sum()
+    }
+}
 
-            if buf.chunks(buf.len() / num_parts).all_equal() {
-                return true;
+/// The most digits a `u64` can have
+const MAX_DIGITS: u32 = u64::MAX.ilog10() + 1;
+
+/// The distinct prime factors of `n`
+fn prime_factors(mut n: u32) -> Vec<u32> {
+    let mut factors = vec![];
+    let mut p = 2;
+    while n > 1 {
+        if n.is_multiple_of(p) {
+            factors.push(p);
+            while n.is_multiple_of(p) {
+                n /= p;
             }
         }
-
-        false
+        p += 1;
     }
+    factors
 }
 
+pub const DAY: Day = Day::new::<Day02>(2, "Gift Shop");
+
 #[derive(Debug)]
 pub struct Day02 {
     ranges: Vec<IdRange>,
 }
 
 impl Solution for Day02 {
-    fn with_input(input: String) -> Self {
-        let ranges = input.trim().split(',').map(IdRange::parse).collect();
-        Self { ranges }
+    fn with_input(input: String, _params: &Params) -> Result<Self, ParseError> {
+        let ranges = parse::parse_lines(&input, |line| {
+            line.split(',')
+                .map(|range| IdRange::parse(line, range))
+                .collect::<Result<Vec<_>, _>>()
+        })?;
+        let ranges = ranges.into_iter().flatten().collect();
+        Ok(Self { ranges })
     }
 
-    fn part1(&self) -> String {
-        let mut buf = Vec::with_capacity(10);
-
+    fn part1(&self) -> Answer {
         self.ranges
             .iter()
-            .flat_map(IdRange::ids)
-            .filter_map(|id| id.contains_double_repeat(&mut buf).then_some(id.0))
-            .sum::<u64>()
-            .to_string()
+            .map(IdRange::sum_double_repeats)
+            .sum::<u128>()
+            .into()
     }
 
-    fn part2(&self) -> String {
-        let mut buf = Vec::with_capacity(10);
+    fn part2(&self) -> Answer {
         self.ranges
             .iter()
-            .flat_map(IdRange::ids)
-            .filter_map(|id| id.contains_any_repeat(&mut buf).then_some(id.0))
-            .sum::<u64>()
-            .to_string()
+            .map(IdRange::sum_any_repeats)
+            .sum::<u128>()
+            .into()
     }
 }
@@ -1,19 +1,31 @@
-use crate::solution::Solution;
+use crate::{
+    answer::Answer,
+    params::{ParamSpec, Params},
+    parse::{self, ParseError},
+    solution::{Day, Solution},
+};
 
 #[derive(Debug, Clone)]
 struct Bank(Vec<u8>);
 
 impl Bank {
-    fn from_line(l: &str) -> Self {
+    fn from_line(l: &str) -> Result<Self, ParseError> {
+        if l.is_empty() {
+            return Err(ParseError::line(l, "expected a bank of batteries"));
+        }
+
         let values = l
-            .chars()
-            .map(|c| {
-                let val = c.to_digit(10).unwrap() as u8;
-                assert!((1..=9).contains(&val));
-                val
+            .char_indices()
+            .map(|(i, c)| match c.to_digit(10) {
+                Some(val @ 1..=9) => Ok(val as u8),
+                _ => Err(ParseError::new(
+                    l,
+                    &l[i..i + c.len_utf8()],
+                    format!("invalid joltage `{c}`, expected 1-9"),
+                )),
             })
-            .collect();
-        Self(values)
+            .collect::<Result<_, _>>()?;
+        Ok(Self(values))
     }
 
     fn max_joltage(&self) -> u32 {
@@ -36,11 +48,11 @@ impl Bank {
         max_l * 10 + max_r
     }
 
-    fn max_12_joltage(&self) -> u64 {
-        // Initialize with the first 12 elements
-        let mut maxes: Vec<_> = self.0[0..12].into();
+    fn max_n_joltage(&self, n: usize) -> u64 {
+        // Initialize with the first n elements
+        let mut maxes: Vec<_> = self.0[0..n].into();
 
-        for next in self.0.iter().skip(12).copied() {
+        for next in self.0.iter().skip(n).copied() {
             // If any element is smaller than the one to its right, pop it out and replace it with
             // the next in the bank
             if let Some(i) = maxes
@@ -65,30 +77,52 @@ impl Bank {
     }
 }
 
+pub const DAY: Day = Day::new::<Day03>(3, "Lobby").with_params(&[BATTERIES]);
+
+// any more than 19 batteries and a bank's joltage overflows a `u64`, so banks are summed in a
+// `u128` that can't overflow however many there are
+const BATTERIES: ParamSpec =
+    ParamSpec::new("batteries", 12, "how many batteries to turn on in part 2").range(1..=19);
+
 #[derive(Debug)]
 pub struct Day03 {
     banks: Vec<Bank>,
+    batteries: usize,
 }
 
 impl Solution for Day03 {
-    fn with_input(input: String) -> Self {
-        let banks = input.trim().lines().map(Bank::from_line).collect();
-        Self { banks }
+    fn with_input(input: String, params: &Params) -> Result<Self, ParseError> {
+        let batteries = params.get("batteries");
+
+        // part 1 always turns on two batteries
+        let min_len = batteries.max(2);
+        let banks = parse::parse_lines(&input, |line| {
+            let bank = Bank::from_line(line)?;
+            if bank.0.len() < min_len {
+                return Err(ParseError::line(
+                    line,
+                    format!("expected at least {min_len} batteries, found {}", bank.0.len()),
+                ));
+            }
+            Ok(bank)
+        })?;
+
+        Ok(Self { banks, batteries })
     }
 
-    fn part1(&self) -> String {
+    fn part1(&self) -> Answer {
         self.banks
             .iter()
             .map(Bank::max_joltage)
             .sum::<u32>()
-            .to_string()
+            .into()
     }
 
-    fn part2(&self) -> String {
+    fn part2(&self) -> Answer {
         self.banks
             .iter()
-            .map(Bank::max_12_joltage)
-            .sum::<u64>()
-            .to_string()
+            .map(|bank| u128::from(bank.max_n_joltage(self.batteries)))
+            .sum::<u128>()
+            .into()
     }
 }
@@ -1,180 +1,451 @@
-use crate::solution::Solution;
+use std::{io::Write, thread, time::Duration};
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
-enum Tile {
-    Empty,
-    Paper,
+use crate::{
+    answer::Answer,
+    params::{ParamSpec, Params},
+    parse::{self, ParseError},
+    solution::{Day, Solution, Tool, ToolOutput},
+};
+
+/// Which tiles count as a tile's neighbours
+#[derive(Clone, Copy, Debug)]
+struct Neighbourhood {
+    /// Whether the four diagonal tiles are neighbours as well as the four orthogonal ones
+    diagonals: bool,
+    /// Whether the grid wraps around, so tiles on opposite edges are neighbours
+    ///
+    /// On a grid less than 3 tiles wide or high, this makes the same tile a neighbour in more than
+    /// one direction, and it's counted once for each.
+    wrap: bool,
+}
+
+impl Default for Neighbourhood {
+    fn default() -> Self {
+        Self {
+            diagonals: true,
+            wrap: false,
+        }
+    }
 }
 
+/// Where the rolls of paper are, one bit per tile, with each row packed into `u64` words
+///
+/// Tile `c` of a row is bit `c % 64` of word `c / 64`, and the bits past the end of a row are
+/// always clear. This lets neighbour counts be worked out for 64 tiles at a time: each neighbour
+/// direction is a shifted copy of a row, and adding those up bit by bit gives each tile's count
+/// spread across four words, one per binary digit.
 #[derive(Clone, Debug)]
-struct Grid<T> {
-    grid: Vec<T>,
+struct Grid {
+    paper: Vec<u64>,
     w: usize,
     h: usize,
+    /// Words per row
+    words: usize,
+    neighbourhood: Neighbourhood,
 }
 
-impl Grid<Tile> {
-    fn from_input(input: &str) -> Self {
-        let (first_line, _) = input.split_once("\n").unwrap();
-        let w = first_line.len();
+impl Grid {
+    fn from_input(input: &str) -> Result<Self, ParseError> {
+        let mut w = None;
+        let mut rows = vec![];
+
+        parse::parse_lines(input, |line| {
+            let mut row = vec![];
+            for (i, c) in line.char_indices() {
+                match c {
+                    '.' => row.push(false),
+                    '@' => row.push(true),
+                    _ => {
+                        let span = &line[i..i + c.len_utf8()];
+                        return Err(ParseError::new(line, span, format!("unexpected char: `{c}`")));
+                    }
+                }
+            }
+
+            let row_w = row.len();
+            rows.push(row);
+            match w {
+                None if row_w == 0 => Err(ParseError::line(line, "expected a row of tiles")),
+                Some(w) if row_w != w => Err(ParseError::line(
+                    line,
+                    format!("expected {w} tiles, found {row_w}"),
+                )),
+                _ => {
+                    w = Some(row_w);
+                    Ok(())
+                }
+            }
+        })?;
 
-        let cap = input.len() - w + 1;
-        let mut grid = Vec::with_capacity(cap);
-        grid.extend(input.chars().filter_map(|c| match c {
-            '.' => Some(Tile::Empty),
-            '@' => Some(Tile::Paper),
-            '\n' => None,
-            _ => panic!("unexpected char: {c}"),
-        }));
+        let Some(w) = w else {
+            return Err(ParseError::end_of_input(input, "expected a grid"));
+        };
+        let words = w.div_ceil(64);
 
-        let h = grid.len() / w;
+        let mut paper = vec![0; rows.len() * words];
+        for (r, row) in rows.iter().enumerate() {
+            for (c, _) in row.iter().enumerate().filter(|(_, is_paper)| **is_paper) {
+                paper[r * words + c / 64] |= 1 << (c % 64);
+            }
+        }
 
-        Self { grid, w, h }
+        Ok(Self {
+            paper,
+            w,
+            h: rows.len(),
+            words,
+            neighbourhood: Neighbourhood::default(),
+        })
     }
 
-    fn tile_is_accessible(&self, coords: Coords) -> bool {
-        let adjacent_paper = self
-            .surroundings(coords)
-            .filter(|s| *s == Tile::Paper)
-            .count();
-        adjacent_paper < 4
+    fn row(&self, r: usize) -> &[u64] {
+        &self.paper[r * self.words..(r + 1) * self.words]
     }
 
-    fn count_accessible(&self) -> usize {
-        self.tiles()
-            .filter(|(coords, t)| *t == Tile::Paper && self.tile_is_accessible(*coords))
-            .count()
+    /// The rows above and below row `r`, if there are any there, which past the edges of the grid
+    /// is only if it wraps
+    fn vertical_neighbours(&self, r: usize) -> [Option<usize>; 2] {
+        let wrap = self.neighbourhood.wrap;
+        let above = match r.checked_sub(1) {
+            Some(above) => Some(above),
+            None => wrap.then_some(self.h - 1),
+        };
+        let below = match r + 1 {
+            below if below < self.h => Some(below),
+            _ => wrap.then_some(0),
+        };
+        [above, below]
     }
 
-    fn remove_accessible(&mut self) -> usize {
-        let mut removed = 0;
+    /// The words before and after word `k` of a row whose edge tiles are neighbours of its tiles,
+    /// which past the ends of the row is only if the grid wraps
+    fn horizontal_neighbours(&self, k: usize) -> [Option<usize>; 2] {
+        let wrap = self.neighbourhood.wrap;
+        let before = match k.checked_sub(1) {
+            Some(before) => Some(before),
+            None => wrap.then_some(self.words - 1),
+        };
+        let after = match k + 1 {
+            after if after < self.words => Some(after),
+            _ => wrap.then_some(0),
+        };
+        [before, after]
+    }
 
-        for i in 0..self.grid.len() {
-            let coords = self.i_to_coords(i);
+    /// Word `k` of `row` shifted so each tile holds its western neighbour
+    fn west(&self, row: &[u64], k: usize) -> u64 {
+        let carry = if k > 0 {
+            row[k - 1] >> 63
+        } else if self.neighbourhood.wrap {
+            row[(self.w - 1) / 64] >> ((self.w - 1) % 64) & 1
+        } else {
+            0
+        };
+        row[k] << 1 | carry
+    }
 
-            // SAFETY: `i` is less than `self.grid.len()`
-            let t = unsafe { self.grid.get_unchecked(i) };
+    /// Word `k` of `row` shifted so each tile holds its eastern neighbour
+    fn east(&self, row: &[u64], k: usize) -> u64 {
+        let carry = if k + 1 < self.words {
+            row[k + 1] << 63
+        } else if self.neighbourhood.wrap {
+            (row[0] & 1) << ((self.w - 1) % 64)
+        } else {
+            0
+        };
+        row[k] >> 1 | carry
+    }
 
-            if *t == Tile::Paper && self.tile_is_accessible(coords) {
-                // SAFETY: `i` is elss than `self.grid.len()`
-                unsafe { *self.grid.get_unchecked_mut(i) = Tile::Empty };
+    /// The rolls in word `k` of row `r` with fewer than `threshold` neighbouring rolls
+    fn accessible(&self, r: usize, k: usize, threshold: usize) -> u64 {
+        let row = self.row(r);
+        let paper = row[k];
+        if paper == 0 {
+            return 0;
+        }
 
-                removed += 1;
+        // each tile's count of neighbouring rolls, as its bit in each of these binary digits
+        let mut count = [0u64; 4];
+        let mut add = |neighbours: u64| {
+            let mut carry = neighbours;
+            for digit in &mut count {
+                (*digit, carry) = (*digit ^ carry, *digit & carry);
+            }
+        };
+
+        add(self.west(row, k));
+        add(self.east(row, k));
+        for vertical in self.vertical_neighbours(r).into_iter().flatten() {
+            let vertical = self.row(vertical);
+            add(vertical[k]);
+            if self.neighbourhood.diagonals {
+                add(self.west(vertical, k));
+                add(self.east(vertical, k));
             }
         }
 
-        removed
-    }
-}
-
-impl<T> Grid<T> {
-    /// SAFETY: the coordinates must be in-bounds
-    unsafe fn get_unchecked(&self, coords: Coords) -> &T {
-        let i = self.coords_to_i(coords);
-        unsafe { self.grid.get_unchecked(i) }
+        let has_count = |n: usize| {
+            count.iter().enumerate().fold(!0, |tiles, (i, digit)| {
+                tiles & if n >> i & 1 == 1 { *digit } else { !digit }
+            })
+        };
+        paper & (0..threshold).fold(0, |tiles, n| tiles | has_count(n))
     }
 
-    fn tiles(&self) -> impl Iterator<Item = (Coords, T)>
-    where
-        T: Copy,
-    {
-        self.grid.iter().copied().enumerate().map(|(i, t)| {
-            let coords = self.i_to_coords(i);
-            (coords, t)
-        })
+    fn count_accessible(&self, threshold: usize) -> usize {
+        (0..self.h)
+            .flat_map(|r| (0..self.words).map(move |k| (r, k)))
+            .map(|(r, k)| self.accessible(r, k, threshold).count_ones() as usize)
+            .sum()
     }
 
-    fn surroundings_coords(&self, coords: Coords) -> impl Iterator<Item = Coords> {
-        assert!(self.coords_to_i(coords) < self.grid.len());
-        [
-            self.row_sub(coords).and_then(|coords| self.col_sub(coords)), // NW
-            self.row_sub(coords),                                         // N
-            self.row_sub(coords).and_then(|coords| self.col_add(coords)), // NE
-            self.col_add(coords),                                         // E
-            self.col_sub(coords),                                         // W
-            self.row_add(coords).and_then(|coords| self.col_sub(coords)), // SW
-            self.row_add(coords),                                         // S
-            self.row_add(coords).and_then(|coords| self.col_add(coords)), // SE
-        ]
-        .into_iter()
-        .flatten()
-    }
-
-    fn surroundings(&self, coords: Coords) -> impl Iterator<Item = T>
-    where
-        T: Copy,
-    {
-        self.surroundings_coords(coords).map(|c| {
-            // SAFETY: the coords are guaranteed to be in-bounds
-            unsafe { *self.get_unchecked(c) }
+    /// The indices, counting across each row in turn, of the tiles set in `tiles`, each a word of
+    /// tiles along with its index in `paper`
+    fn tile_indices(
+        &self,
+        tiles: impl IntoIterator<Item = (usize, u64)>,
+    ) -> impl Iterator<Item = usize> {
+        let (w, words) = (self.w, self.words);
+        tiles.into_iter().flat_map(move |(i, word)| {
+            let (r, k) = (i / words, i % words);
+            (0..64)
+                .filter(move |bit| word >> bit & 1 == 1)
+                .map(move |bit| r * w + k * 64 + bit)
         })
     }
+}
 
-    fn i_to_coords(&self, i: usize) -> Coords {
-        debug_assert!(i < self.grid.len());
-
-        let r = i / self.w;
-        let c = i - r * self.w;
+/// The rounds of erosion, where each round removes every roll that's accessible once the previous
+/// rounds' rolls are gone
+///
+/// Each round is the words of `paper` it removed rolls from, as each word's index and the rolls
+/// removed from it. Only the words next to one that lost rolls in the last round can have changed,
+/// so only they are looked at again.
+struct Erosion {
+    grid: Grid,
+    threshold: usize,
+    /// The indices of the words of `paper` to look at in the next round
+    dirty: Vec<usize>,
+    /// Whether each word of `paper` is in `dirty`
+    is_dirty: Vec<bool>,
+}
 
-        Coords(r, c)
+impl Erosion {
+    fn new(grid: &Grid, threshold: usize) -> Self {
+        Self {
+            grid: grid.clone(),
+            threshold,
+            dirty: (0..grid.paper.len()).collect(),
+            is_dirty: vec![true; grid.paper.len()],
+        }
     }
+}
 
-    fn coords_to_i(&self, Coords(r, c): Coords) -> usize {
-        r * self.w + c
-    }
+impl Iterator for Erosion {
+    type Item = Vec<(usize, u64)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let words = self.grid.words;
+        let mut removed = vec![];
+        for i in self.dirty.drain(..) {
+            self.is_dirty[i] = false;
+            let accessible = self.grid.accessible(i / words, i % words, self.threshold);
+            if accessible != 0 {
+                removed.push((i, accessible));
+            }
+        }
+        if removed.is_empty() {
+            return None;
+        }
 
-    fn row_sub(&self, Coords(r, c): Coords) -> Option<Coords> {
-        debug_assert!(r < self.h);
-        r.checked_sub(1).map(|r| Coords(r, c))
+        for &(i, accessible) in &removed {
+            self.grid.paper[i] &= !accessible;
+
+            let (r, k) = (i / words, i % words);
+            let [above, below] = self.grid.vertical_neighbours(r);
+            let [before, after] = self.grid.horizontal_neighbours(k);
+            for r in [above, Some(r), below].into_iter().flatten() {
+                for k in [before, Some(k), after].into_iter().flatten() {
+                    let i = r * words + k;
+                    if !self.is_dirty[i] {
+                        self.is_dirty[i] = true;
+                        self.dirty.push(i);
+                    }
+                }
+            }
+        }
+        Some(removed)
     }
+}
 
-    fn row_add(&self, Coords(r, c): Coords) -> Option<Coords> {
-        debug_assert!(r < self.h);
-        (r < self.h - 1).then_some(Coords(r + 1, c))
-    }
+/// A snapshot of the grid during erosion
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum FrameTile {
+    Empty,
+    Paper,
+    /// Removed in the round the frame shows
+    Removed,
+}
 
-    fn col_sub(&self, Coords(r, c): Coords) -> Option<Coords> {
-        debug_assert!(c < self.w);
-        c.checked_sub(1).map(|c| Coords(r, c))
+/// The grid before erosion, then after each round, along with how many rolls the round removed
+fn erosion_frames(
+    grid: &Grid,
+    threshold: usize,
+) -> impl Iterator<Item = (Vec<FrameTile>, usize)> {
+    let mut frame = vec![FrameTile::Empty; grid.w * grid.h];
+    for i in grid.tile_indices(grid.paper.iter().copied().enumerate()) {
+        frame[i] = FrameTile::Paper;
     }
+    let initial = (frame.clone(), 0);
 
-    fn col_add(&self, Coords(r, c): Coords) -> Option<Coords> {
-        debug_assert!(c < self.w);
-        (c < self.w - 1).then_some(Coords(r, c + 1))
-    }
+    let mut last_removed = vec![];
+    let rounds = Erosion::new(grid, threshold).map(move |round| {
+        for &i in &last_removed {
+            frame[i] = FrameTile::Empty;
+        }
+        last_removed = grid.tile_indices(round).collect();
+        for &i in &last_removed {
+            frame[i] = FrameTile::Removed;
+        }
+        (frame.clone(), last_removed.len())
+    });
+
+    std::iter::once(initial).chain(rounds)
 }
 
-#[derive(Clone, Copy, Debug)]
-struct Coords(usize, usize);
+const PARAMS: &[ParamSpec] = &[
+    ParamSpec::new(
+        "threshold",
+        4,
+        "a roll is accessible with fewer than this many neighbouring rolls",
+    )
+    .range(0..=9),
+    ParamSpec::new(
+        "diagonals",
+        1,
+        "whether diagonal tiles are neighbours (1) or only orthogonal ones (0)",
+    )
+    .range(0..=1),
+    ParamSpec::new(
+        "wrap",
+        0,
+        "whether the grid wraps around at its edges (1) or not (0)",
+    )
+    .range(0..=1),
+];
+
+const TOOLS: &[Tool] = &[
+    Tool::new(
+        "pgm",
+        "write each round of part 2's erosion as a numbered PGM image",
+    ),
+    Tool::new("animate", "animate part 2's erosion in the terminal"),
+];
+
+pub const DAY: Day = Day::new::<Day04>(4, "Printing Department")
+    .with_params(PARAMS)
+    .with_tools(TOOLS);
 
 #[derive(Debug)]
 pub struct Day04 {
-    grid: Grid<Tile>,
+    grid: Grid,
+    threshold: usize,
 }
 
-impl Solution for Day04 {
-    fn with_input(input: String) -> Self {
-        let grid = Grid::from_input(input.trim());
-        Self { grid }
+impl Day04 {
+    /// Write each frame of the erosion as a binary PGM image, with paper in black, empty tiles in
+    /// white and the rolls the round removed in grey
+    fn write_pgm(&self, output: &mut ToolOutput) -> std::io::Result<()> {
+        let mut removed = 0;
+        for (n, (frame, count)) in erosion_frames(&self.grid, self.threshold).enumerate() {
+            removed += count;
+            let mut out = output.numbered(n)?;
+            write!(
+                out,
+                "P5\n# round {n}: removed {count} rolls, {removed} in total\n{} {}\n255\n",
+                self.grid.w, self.grid.h
+            )?;
+            let pixels = frame
+                .iter()
+                .map(|t| match t {
+                    FrameTile::Empty => 0xff,
+                    FrameTile::Paper => 0x00,
+                    FrameTile::Removed => 0x80,
+                })
+                .collect::<Vec<u8>>();
+            out.write_all(&pixels)?;
+            out.flush()?;
+        }
+        Ok(())
     }
 
-    fn part1(&self) -> String {
-        self.grid.count_accessible().to_string()
-    }
+    /// Draw each frame of the erosion over the last with ANSI escape codes, highlighting the rolls
+    /// the round removed, and pausing between frames when drawing them in a terminal
+    fn animate(&self, output: &mut ToolOutput) -> std::io::Result<()> {
+        const FRAME_TIME: Duration = Duration::from_millis(200);
+        const CLEAR: &str = "\x1b[2J";
+        const HOME: &str = "\x1b[H";
+        const REMOVED: &str = "\x1b[1;31m@\x1b[0m";
 
-    fn part2(&self) -> String {
-        let mut total_removed = 0;
-        let mut grid = self.grid.clone();
+        // there's no one to watch the frames go by in a file or pipe
+        let pause = output.is_terminal();
+        let out = output.out()?;
 
-        loop {
-            let removed = grid.remove_accessible();
-            if removed == 0 {
-                break;
-            } else {
-                total_removed += removed;
+        write!(out, "{CLEAR}")?;
+        let mut removed = 0;
+        for (n, (frame, count)) in erosion_frames(&self.grid, self.threshold).enumerate() {
+            removed += count;
+            write!(out, "{HOME}")?;
+            for row in frame.chunks(self.grid.w) {
+                for t in row {
+                    match t {
+                        FrameTile::Empty => write!(out, ".")?,
+                        FrameTile::Paper => write!(out, "@")?,
+                        FrameTile::Removed => write!(out, "{REMOVED}")?,
+                    }
+                }
+                writeln!(out)?;
+            }
+            writeln!(out, "Round {n}: removed {count} rolls, {removed} in total")?;
+            out.flush()?;
+            if pause {
+                thread::sleep(FRAME_TIME);
             }
         }
+        Ok(())
+    }
+}
+
+impl Solution for Day04 {
+    fn with_input(input: String, params: &Params) -> Result<Self, ParseError> {
+        let mut grid = Grid::from_input(&input)?;
+        grid.neighbourhood = Neighbourhood {
+            diagonals: params.get("diagonals") == 1,
+            wrap: params.get("wrap") == 1,
+        };
+        Ok(Self {
+            grid,
+            threshold: params.get("threshold"),
+        })
+    }
+
+    fn part1(&self) -> Answer {
+        self.grid.count_accessible(self.threshold).into()
+    }
+
+    fn part2(&self) -> Answer {
+        Erosion::new(&self.grid, self.threshold)
+            .flatten()
+            .map(|(_, removed)| removed.count_ones() as usize)
+            .sum::<usize>()
+            .into()
+    }
 
-        total_removed.to_string()
+    fn run_tool(&self, name: &str, output: &mut ToolOutput) -> std::io::Result<()> {
+        match name {
+            "pgm" => self.write_pgm(output),
+            "animate" => self.animate(output),
+            _ => Err(Tool::unknown(name)),
+        }
     }
 }
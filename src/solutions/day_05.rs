@@ -1,6 +1,29 @@
+use std::ops::RangeInclusive;
+
 use rangemap::RangeInclusiveSet;
 
-use crate::solution::Solution;
+use crate::{
+    answer::Answer,
+    params::Params,
+    parse::{self, ParseError},
+    solution::{Day, Solution},
+};
+
+pub const DAY: Day = Day::new::<Day05>(5, "Cafeteria");
+
+fn parse_range(line: &str) -> Result<RangeInclusive<u64>, ParseError> {
+    let Some((from, to)) = line.split_once('-') else {
+        return Err(ParseError::line(line, "expected a range like `3-5`"));
+    };
+
+    let from = parse::parse_number(line, from)?;
+    let to = parse::parse_number(line, to)?;
+    if from > to {
+        return Err(ParseError::line(line, "range is empty"));
+    }
+
+    Ok(from..=to)
+}
 
 #[derive(Debug)]
 pub struct Day05 {
@@ -9,37 +32,49 @@ pub struct Day05 {
 }
 
 impl Solution for Day05 {
-    fn with_input(input: String) -> Self {
-        let (ranges, ingredients) = input.split_once("\n\n").unwrap();
-
-        let ranges = ranges
-            .lines()
-            .map(|line| {
-                let (from, to) = line.split_once('-').unwrap();
-                from.parse().unwrap()..=to.parse().unwrap()
-            })
-            .collect();
-        let ingredients = ingredients.lines().map(|l| l.parse().unwrap()).collect();
-
-        Self {
+    fn with_input(input: String, _params: &Params) -> Result<Self, ParseError> {
+        let mut lines = input.trim_end().lines().enumerate();
+
+        let mut ranges = RangeInclusiveSet::new();
+        let mut found_separator = false;
+        for (i, line) in lines.by_ref() {
+            if line.is_empty() {
+                found_separator = true;
+                break;
+            }
+            ranges.insert(parse_range(line).map_err(|e| e.offset_lines(i))?);
+        }
+
+        if !found_separator {
+            return Err(ParseError::end_of_input(
+                &input,
+                "expected a blank line followed by ingredients",
+            ));
+        }
+
+        let ingredients = lines
+            .map(|(i, line)| parse::parse_number(line, line).map_err(|e| e.offset_lines(i)))
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self {
             ranges,
             ingredients,
-        }
+        })
     }
 
-    fn part1(&self) -> String {
+    fn part1(&self) -> Answer {
         self.ingredients
             .iter()
             .filter(|i| self.ranges.contains(i))
             .count()
-            .to_string()
+            .into()
     }
 
-    fn part2(&self) -> String {
+    fn part2(&self) -> Answer {
         self.ranges
             .iter()
             .map(|r| r.end() - r.start() + 1)
             .sum::<u64>()
-            .to_string()
+            .into()
     }
 }
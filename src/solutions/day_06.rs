@@ -1,140 +1,216 @@
-use itertools::Itertools;
+use std::ops::Range;
 
-use crate::solution::Solution;
+use crate::{
+    answer::Answer,
+    params::Params,
+    parse::{self, ParseError},
+    solution::{Day, Solution},
+};
 
 #[derive(Clone, Debug)]
 enum Problem {
-    Add(Vec<u32>),
-    Mul(Vec<u32>),
+    Add(Vec<u64>),
+    Mul(Vec<u64>),
 }
 
 impl Problem {
-    fn eval(&self) -> u64 {
-        match self {
-            Self::Add(v) => v.iter().map(|v| *v as u64).sum(),
-            Self::Mul(v) => v.iter().copied().fold(1, |acc, v| acc * v as u64),
+    fn new(op: u8, numbers: Vec<u64>) -> Self {
+        match op {
+            b'+' => Self::Add(numbers),
+            _ => Self::Mul(numbers),
         }
     }
-}
 
-#[derive(Debug)]
-pub struct Day06 {
-    lines: Vec<Vec<u8>>,
+    /// The problem's answer, or `None` if it doesn't fit in a `u128`
+    fn eval(&self) -> Option<u128> {
+        match self {
+            Self::Add(v) => v
+                .iter()
+                .try_fold(0u128, |acc, &v| acc.checked_add(v.into())),
+            Self::Mul(v) => v
+                .iter()
+                .try_fold(1u128, |acc, &v| acc.checked_mul(v.into())),
+        }
+    }
 }
 
-fn bytes_to_u32(digits: &[u8]) -> u32 {
-    let pow = (digits.len() - 1) as u32;
-    digits
+/// The sum of every problem's answer, or unsolved if it doesn't fit in a `u128`
+fn grand_total(problems: &[Problem]) -> Answer {
+    problems
         .iter()
-        .copied()
-        .enumerate()
-        .map(|(i, b)| {
-            debug_assert!(b.is_ascii_digit());
-            let d = (b - b'0') as u32;
-            10u32.pow(pow - i as u32) * d
-        })
-        .sum()
+        .try_fold(0u128, |acc, p| acc.checked_add(p.eval()?))
+        .map_or(Answer::Unsolved, Answer::from)
 }
 
-impl Day06 {
-    fn parse_input_part1(&self) -> Vec<Problem> {
-        let mut problem_inputs = Vec::<Vec<u32>>::new();
-        for line in &self.lines {
-            if matches!(line[0], b'+' | b'*') {
-                return problem_inputs
-                    .into_iter()
-                    .zip(line.split(|b| *b == b' ').filter(|slice| !slice.is_empty()))
-                    .map(|(inputs, op)| match op[0] {
-                        b'+' => Problem::Add(inputs),
-                        b'*' => Problem::Mul(inputs),
-                        _ => panic!("invalid op"),
-                    })
-                    .collect();
-            }
+pub const DAY: Day = Day::new::<Day06>(6, "Trash Compactor");
 
-            for (i, n) in line
-                .split(|b| *b == b' ')
-                .filter(|slice| !slice.is_empty())
-                .enumerate()
-            {
-                if i >= problem_inputs.len() {
-                    problem_inputs.push(Vec::new())
-                }
+#[derive(Debug)]
+pub struct Day06 {
+    /// The problems read a row at a time
+    rows: Vec<Problem>,
+    /// The problems read a column at a time
+    columns: Vec<Problem>,
+}
 
-                problem_inputs[i].push(bytes_to_u32(n))
-            }
+/// Check that `line` only has the chars the parsers expect, and is as wide as the other lines
+fn validate_line(line: &str, width: usize, is_op_line: bool) -> Result<(), ParseError> {
+    for (i, c) in line.char_indices() {
+        let (valid, expected) = if is_op_line {
+            (matches!(c, '+' | '*' | ' '), "expected `+`, `*` or a space")
+        } else {
+            (
+                c == ' ' || c.is_ascii_digit(),
+                "expected a digit or a space",
+            )
+        };
+        if !valid {
+            return Err(ParseError::new(line, &line[i..i + c.len_utf8()], expected));
         }
+    }
 
-        panic!("no op line found");
+    if line.len() != width {
+        return Err(ParseError::line(
+            line,
+            format!("expected {width} columns, found {}", line.len()),
+        ));
     }
 
-    fn parse_input_part2(&self) -> Vec<Problem> {
-        let mut problems = Vec::new();
-
-        let mut current_problem = Vec::new();
-        let mut digits = Vec::new();
-        let mut current_op = None;
-        for col in 0..self.lines[0].len() {
-            digits.clear();
-            digits.extend((0..self.lines.len() - 1).filter_map(|row| {
-                let b = self.lines[row][col];
-                if b.is_ascii_digit() { Some(b) } else { None }
-            }));
-
-            if digits.is_empty() {
-                let problem = match current_op.take().unwrap() {
-                    b'+' => Problem::Add(current_problem.clone()),
-                    b'*' => Problem::Mul(current_problem.clone()),
-                    _ => panic!(),
-                };
-                problems.push(problem);
-                current_problem.clear();
-
-                continue;
-            }
+    if is_op_line && !line.starts_with(['+', '*']) {
+        return Err(ParseError::new(line, &line[..1], "expected an operator"));
+    }
 
-            current_problem.push(bytes_to_u32(&digits));
+    Ok(())
+}
 
-            let last = self.lines.last().unwrap()[col];
-            if matches!(last, b'+' | b'*') {
-                current_op = Some(last);
-            }
+/// The columns each problem spans, which are separated by single blank columns
+fn problem_columns(lines: &[&str]) -> Result<Vec<Range<usize>>, ParseError> {
+    let ops = lines[lines.len() - 1];
+    let blank = |col: usize| lines.iter().all(|l| l.as_bytes()[col] == b' ');
+
+    let mut problems = vec![];
+    let mut start = 0;
+    for col in (0..ops.len()).filter(|&col| blank(col)).chain([ops.len()]) {
+        if col == start {
+            let col = col.min(ops.len() - 1);
+            return Err(ParseError::new(
+                ops,
+                &ops[col..=col],
+                "expected a single blank column between problems",
+            ));
         }
+        problems.push(start..col);
+        start = col + 1;
+    }
 
-        let problem = match current_op.unwrap() {
-            b'+' => Problem::Add(current_problem.clone()),
-            b'*' => Problem::Mul(current_problem.clone()),
-            _ => panic!(),
-        };
-        problems.push(problem);
-
-        problems
+    for columns in &problems {
+        let op = &ops[columns.start..=columns.start];
+        if op == " " {
+            return Err(ParseError::new(
+                ops,
+                op,
+                "expected an operator in the first column of the problem",
+            ));
+        }
+        if let Some(i) = ops[columns.start + 1..columns.end].find(['+', '*']) {
+            let i = columns.start + 1 + i;
+            return Err(ParseError::new(
+                ops,
+                &ops[i..=i],
+                "expected one operator per problem",
+            ));
+        }
     }
+
+    Ok(problems)
+}
+
+/// Read the problem in `columns` of `lines` with one number per row, returning the index of the
+/// line with the error if there is one
+fn parse_rows(lines: &[&str], columns: &Range<usize>) -> Result<Problem, (usize, ParseError)> {
+    let (ops, numbers) = lines.split_last().unwrap();
+    let values = numbers
+        .iter()
+        .enumerate()
+        .map(|(i, line)| {
+            let span = line[columns.clone()].trim();
+            if span.is_empty() || span.contains(' ') {
+                let error = ParseError::new(
+                    line,
+                    &line[columns.clone()],
+                    "expected one number in each row of the problem",
+                );
+                return Err((i, error));
+            }
+            parse::parse_number(line, span).map_err(|e| (i, e))
+        })
+        .collect::<Result<_, _>>()?;
+    Ok(Problem::new(ops.as_bytes()[columns.start], values))
+}
+
+/// Read the problem in `columns` of `lines` with one number per column, its digits going down
+/// the rows, pointing any error at the top of the column
+fn parse_columns(lines: &[&str], columns: &Range<usize>) -> Result<Problem, ParseError> {
+    let (ops, numbers) = lines.split_last().unwrap();
+    let top = numbers[0];
+    let values = columns
+        .clone()
+        .map(|col| {
+            let digits = numbers
+                .iter()
+                .map(|line| line.as_bytes()[col])
+                .filter(u8::is_ascii_digit)
+                .map(char::from)
+                .collect::<String>();
+            digits.parse().map_err(|e| {
+                ParseError::new(
+                    top,
+                    &top[col..=col],
+                    format!("invalid number `{digits}`: {e}"),
+                )
+            })
+        })
+        .collect::<Result<_, _>>()?;
+    Ok(Problem::new(ops.as_bytes()[columns.start], values))
 }
 
 impl Solution for Day06 {
-    fn with_input(input: String) -> Self {
-        let lines = input
+    fn with_input(input: String, _params: &Params) -> Result<Self, ParseError> {
+        let (indices, lines): (Vec<_>, Vec<_>) = input
             .lines()
-            .filter(|l| !l.is_empty())
-            .map(|l| l.bytes().collect_vec())
-            .collect();
-        Self { lines }
+            .enumerate()
+            .filter(|(_, l)| !l.is_empty())
+            .unzip();
+
+        if lines.len() < 2 {
+            return Err(ParseError::end_of_input(
+                &input,
+                "expected rows of numbers followed by a row of operators",
+            ));
+        }
+
+        let width = lines[0].len();
+        for (n, line) in lines.iter().enumerate() {
+            let is_op_line = n == lines.len() - 1;
+            validate_line(line, width, is_op_line).map_err(|e| e.offset_lines(indices[n]))?;
+        }
+
+        let problems =
+            problem_columns(&lines).map_err(|e| e.offset_lines(indices[lines.len() - 1]))?;
+        let mut rows = vec![];
+        let mut columns = vec![];
+        for range in &problems {
+            rows.push(parse_rows(&lines, range).map_err(|(n, e)| e.offset_lines(indices[n]))?);
+            columns.push(parse_columns(&lines, range).map_err(|e| e.offset_lines(indices[0]))?);
+        }
+
+        Ok(Self { rows, columns })
     }
 
-    fn part1(&self) -> String {
-        self.parse_input_part1()
-            .iter()
-            .map(Problem::eval)
-            .sum::<u64>()
-            .to_string()
+    fn part1(&self) -> Answer {
+        grand_total(&self.rows)
     }
 
-    fn part2(&self) -> String {
-        self.parse_input_part2()
-            .iter()
-            .map(Problem::eval)
-            .sum::<u64>()
-            .to_string()
+    fn part2(&self) -> Answer {
+        grand_total(&self.columns)
     }
 }
@@ -2,7 +2,12 @@ use std::collections::HashMap;
 
 use itertools::Itertools;
 
-use crate::solution::Solution;
+use crate::{
+    answer::Answer,
+    params::Params,
+    parse::ParseError,
+    solution::{Day, Solution},
+};
 
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
 enum State {
@@ -34,6 +39,38 @@ impl ManifoldState {
     }
 }
 
+pub const DAY: Day = Day::new::<Day07>(7, "Laboratories");
+
+/// Check that `row` only has the chars the solver expects, and is `width` chars wide
+fn validate_row(row: &str, width: usize, is_first: bool) -> Result<(), ParseError> {
+    for (i, c) in row.char_indices() {
+        let span = &row[i..i + c.len_utf8()];
+        match c {
+            '.' => {}
+            'S' if is_first => {}
+            'S' => return Err(ParseError::new(row, span, "beam source not on the first row")),
+            '^' if i == 0 || i + 1 == width => {
+                return Err(ParseError::new(
+                    row,
+                    span,
+                    "splitter on the edge of the manifold",
+                ));
+            }
+            '^' => {}
+            _ => return Err(ParseError::new(row, span, format!("unexpected char: `{c}`"))),
+        }
+    }
+
+    if row.len() != width {
+        return Err(ParseError::line(
+            row,
+            format!("expected {width} columns, found {}", row.len()),
+        ));
+    }
+
+    Ok(())
+}
+
 #[derive(Debug)]
 pub struct Day07 {
     width: usize,
@@ -42,12 +79,23 @@ pub struct Day07 {
 }
 
 impl Solution for Day07 {
-    fn with_input(input: String) -> Self {
-        let width = input.find('\n').unwrap();
-        let beam_source = input.find('S').unwrap();
+    fn with_input(input: String, _params: &Params) -> Result<Self, ParseError> {
+        let lines = input.trim_end().lines().collect_vec();
+        let Some(first) = lines.first() else {
+            return Err(ParseError::end_of_input(&input, "expected a manifold"));
+        };
+
+        let width = first.len();
+        for (i, line) in lines.iter().enumerate() {
+            validate_row(line, width, i == 0).map_err(|e| e.offset_lines(i))?;
+        }
 
-        let splitters = input
-            .lines()
+        let Some(beam_source) = first.find('S') else {
+            return Err(ParseError::line(first, "expected a beam source `S`"));
+        };
+
+        let splitters = lines
+            .iter()
             .skip(2)
             .enumerate()
             .filter_map(|(i, l)| {
@@ -60,24 +108,24 @@ impl Solution for Day07 {
             })
             .collect_vec();
 
-        Self {
+        Ok(Self {
             width,
             beam_source,
             splitters,
-        }
+        })
     }
 
-    fn part1(&self) -> String {
+    fn part1(&self) -> Answer {
         let mut state = ManifoldState::new(self.width, self.beam_source);
         let mut activations = 0;
         for splitters in &self.splitters {
             activations += state.split(splitters);
         }
 
-        activations.to_string()
+        activations.into()
     }
 
-    fn part2(&self) -> String {
+    fn part2(&self) -> Answer {
         let mut map = HashMap::<(usize, usize), u64>::new();
 
         for (i, row) in self.splitters.iter().enumerate().rev() {
@@ -95,6 +143,11 @@ impl Solution for Day07 {
             }
         }
 
-        map.get(&(0, self.beam_source)).unwrap().to_string()
+        // a beam that never meets a splitter stays a single timeline
+        (0..self.splitters.len())
+            .find_map(|i| map.get(&(i, self.beam_source)))
+            .copied()
+            .unwrap_or(1)
+            .into()
     }
 }
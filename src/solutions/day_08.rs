@@ -1,115 +1,713 @@
-use std::collections::HashMap;
-
-use itertools::Itertools;
-use petgraph::{
-    algo::{connected_components, kosaraju_scc},
-    prelude::*,
+use std::{
+    cmp::Reverse,
+    collections::{BTreeMap, BinaryHeap, HashMap},
+    io::Write,
+    ops::Range,
 };
 
-use crate::solution::Solution;
+use itertools::Itertools;
 
-type Graph = UnGraph<JBox, ()>;
+use crate::{
+    answer::Answer,
+    params::{ParamSpec, Params},
+    parse::{self, ParseError},
+    solution::{Day, Solution, Tool, ToolOutput},
+};
 
+/// A junction box's coordinates, which fit in an `i32` so that squared distances between boxes
+/// are exact in an `i128`
 #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
 struct JBox(i64, i64, i64);
 
 impl JBox {
-    fn parse(line: &str) -> Self {
-        let (x, y, z) = line
-            .split(',')
-            .map(|n| n.parse().unwrap())
-            .collect_tuple()
-            .unwrap();
-        Self(x, y, z)
+    fn parse(line: &str) -> Result<Self, ParseError> {
+        let Some((x, y, z)) = line.split(',').collect_tuple() else {
+            return Err(ParseError::line(
+                line,
+                "expected three coordinates like `162,817,812`",
+            ));
+        };
+        let coord = |s| parse::parse_number::<i32>(line, s).map(i64::from);
+        Ok(Self(coord(x)?, coord(y)?, coord(z)?))
+    }
+
+    fn coords(&self) -> [i64; 3] {
+        [self.0, self.1, self.2]
+    }
+
+    fn squared_distance_to(&self, other: &Self) -> i128 {
+        [self.0 - other.0, self.1 - other.1, self.2 - other.2]
+            .iter()
+            .map(|&n| i128::from(n).pow(2))
+            .sum()
+    }
+}
+
+/// A k-d tree over the boxes, for finding the boxes near one without measuring the distance to
+/// every other
+struct KdTree<'a> {
+    boxes: &'a [JBox],
+    /// Box indices, arranged so that each node's boxes are next to each other
+    order: Vec<usize>,
+    nodes: Vec<KdNode>,
+}
+
+struct KdNode {
+    /// The opposite corners of the bounding box of the node's boxes
+    min: [i64; 3],
+    max: [i64; 3],
+    /// The node's boxes, as a range of `order`
+    range: Range<usize>,
+    /// The indices of the node's children in `nodes`, unless it's a leaf
+    children: Option<[usize; 2]>,
+}
+
+impl KdNode {
+    /// The squared distance from `b` to the closest point of the node's bounding box
+    fn min_distance(&self, b: &JBox) -> i128 {
+        (0..3)
+            .map(|axis| {
+                let p = b.coords()[axis];
+                i128::from(p - p.clamp(self.min[axis], self.max[axis])).pow(2)
+            })
+            .sum()
+    }
+
+    /// The squared distance from `b` to the furthest point of the node's bounding box
+    fn max_distance(&self, b: &JBox) -> i128 {
+        (0..3)
+            .map(|axis| {
+                let p = b.coords()[axis];
+                i128::from((p - self.min[axis]).max(self.max[axis] - p)).pow(2)
+            })
+            .sum()
+    }
+}
+
+impl<'a> KdTree<'a> {
+    const LEAF_SIZE: usize = 8;
+
+    fn new(boxes: &'a [JBox]) -> Self {
+        let mut tree = Self {
+            boxes,
+            order: (0..boxes.len()).collect(),
+            nodes: vec![],
+        };
+        tree.build(0..boxes.len());
+        tree
+    }
+
+    /// Add the node covering `range` of `order` and all of its descendants, returning its index
+    fn build(&mut self, range: Range<usize>) -> usize {
+        let (mut min, mut max) = ([i64::MAX; 3], [i64::MIN; 3]);
+        for &i in &self.order[range.clone()] {
+            for (axis, p) in self.boxes[i].coords().into_iter().enumerate() {
+                min[axis] = min[axis].min(p);
+                max[axis] = max[axis].max(p);
+            }
+        }
+
+        let node = self.nodes.len();
+        self.nodes.push(KdNode {
+            min,
+            max,
+            range: range.clone(),
+            children: None,
+        });
+
+        if range.len() > Self::LEAF_SIZE {
+            let axis = (0..3).max_by_key(|&axis| max[axis] - min[axis]).unwrap();
+            let boxes = self.boxes;
+            let mid = range.len() / 2;
+            self.order[range.clone()]
+                .select_nth_unstable_by_key(mid, |&i| boxes[i].coords()[axis]);
+            let left = self.build(range.start..range.start + mid);
+            let right = self.build(range.start + mid..range.end);
+            self.nodes[node].children = Some([left, right]);
+        }
+        node
+    }
+
+    /// The children of `node`, along with the squared distance from `b` to each one's bounding
+    /// box, closer first
+    fn children_by_distance(&self, node: &KdNode, b: &JBox) -> Option<[(usize, i128); 2]> {
+        let [left, right] = node.children?;
+        let left = (left, self.nodes[left].min_distance(b));
+        let right = (right, self.nodes[right].min_distance(b));
+        Some(if left.1 <= right.1 { [left, right] } else { [right, left] })
+    }
+
+    /// Up to `k` of the boxes nearest box `i`, closest first, as their squared distance from it
+    /// and their index, taking only those that come after `after` in that order
+    fn nearest(&self, i: usize, k: usize, after: Option<(i128, usize)>) -> Vec<(i128, usize)> {
+        let mut found = BinaryHeap::with_capacity(k + 1);
+        self.find_nearest((0, 0), i, k, after, &mut found);
+        found.into_sorted_vec()
+    }
+
+    /// Add the boxes in `node` to `found` for [`KdTree::nearest`], given the squared distance to
+    /// its bounding box
+    fn find_nearest(
+        &self,
+        (node, distance): (usize, i128),
+        i: usize,
+        k: usize,
+        after: Option<(i128, usize)>,
+        found: &mut BinaryHeap<(i128, usize)>,
+    ) {
+        let (node, b) = (&self.nodes[node], &self.boxes[i]);
+        if found.len() == k && found.peek().is_some_and(|&(d, _)| distance > d) {
+            return;
+        }
+        // every box in the node is closer than the ones wanted
+        if after.is_some_and(|(d, _)| node.max_distance(b) < d) {
+            return;
+        }
+
+        if let Some(children) = self.children_by_distance(node, b) {
+            for child in children {
+                self.find_nearest(child, i, k, after, found);
+            }
+            return;
+        }
+
+        for &j in self.order[node.range.clone()].iter().filter(|&&j| j != i) {
+            let neighbour = (b.squared_distance_to(&self.boxes[j]), j);
+            if after.is_some_and(|after| neighbour <= after) {
+                continue;
+            }
+            if found.len() < k {
+                found.push(neighbour);
+            } else if found.peek().is_some_and(|&furthest| neighbour < furthest) {
+                found.pop();
+                found.push(neighbour);
+            }
+        }
+    }
+
+    /// For each node, the root of the circuit all of its boxes are in, if they're all in one,
+    /// given the root of each box's circuit
+    fn uniform_circuits(&self, circuit: &[usize]) -> Vec<Option<usize>> {
+        let mut uniform = vec![None; self.nodes.len()];
+        // nodes come before their children, so going backwards reaches the children first
+        for (n, node) in self.nodes.iter().enumerate().rev() {
+            uniform[n] = match node.children {
+                Some([left, right]) => uniform[left].filter(|&c| uniform[right] == Some(c)),
+                None => {
+                    let mut circuits = self.order[node.range.clone()].iter().map(|&i| circuit[i]);
+                    let first = circuits.next();
+                    first.filter(|&c| circuits.all(|other| other == c))
+                }
+            };
+        }
+        uniform
+    }
+
+    /// Replace `closest` with the closest pair between box `i` and a box in another circuit, if
+    /// it's closer, searching from node `n`
+    fn closest_elsewhere(
+        &self,
+        (n, distance): (usize, i128),
+        i: usize,
+        circuit: &[usize],
+        uniform: &[Option<usize>],
+        closest: &mut Option<(i128, usize, usize)>,
+    ) {
+        let (node, b) = (&self.nodes[n], &self.boxes[i]);
+        if uniform[n] == Some(circuit[i]) {
+            return;
+        }
+        if closest.is_some_and(|(d, ..)| distance > d) {
+            return;
+        }
+
+        if let Some(children) = self.children_by_distance(node, b) {
+            for child in children {
+                self.closest_elsewhere(child, i, circuit, uniform, closest);
+            }
+            return;
+        }
+
+        for &j in &self.order[node.range.clone()] {
+            if circuit[j] == circuit[i] {
+                continue;
+            }
+            let pair = (b.squared_distance_to(&self.boxes[j]), i.min(j), i.max(j));
+            if closest.is_none_or(|closest| pair < closest) {
+                *closest = Some(pair);
+            }
+        }
+    }
+}
+
+/// Every pair of boxes, as indices, from closest to furthest apart, found lazily from each box's
+/// nearest neighbours
+///
+/// Distances are compared exactly, as squares. Pairs the same distance apart are ordered by their
+/// indices, so by where their boxes are in the input: `(a, b)` before `(a, c)` if `b < c`, and
+/// before `(b, c)` if `a < b`.
+///
+/// Each pair turns up as a neighbour of both of its boxes, and is only taken from the first.
+struct PairsByDistance<'a> {
+    tree: KdTree<'a>,
+    neighbours: Vec<Neighbours>,
+    /// Each box's closest neighbour not yet taken, as `(distance, a, b, box)` with `a < b`
+    heads: BinaryHeap<Reverse<(i128, usize, usize, usize)>>,
+}
+
+/// The neighbours of a box found so far
+struct Neighbours {
+    /// The neighbours not yet taken, furthest first so the closest can be popped off the end
+    next: Vec<(i128, usize)>,
+    /// The furthest neighbour found, which the next batch starts after
+    furthest: Option<(i128, usize)>,
+    /// How many neighbours to find in the next batch, or 0 once they've all been found
+    batch: usize,
+}
+
+impl<'a> PairsByDistance<'a> {
+    fn new(boxes: &'a [JBox]) -> Self {
+        let mut pairs = Self {
+            tree: KdTree::new(boxes),
+            neighbours: (0..boxes.len())
+                .map(|_| Neighbours {
+                    next: vec![],
+                    furthest: None,
+                    // part 1 takes about as many pairs as there are boxes, a couple for each box
+                    batch: 4,
+                })
+                .collect(),
+            heads: BinaryHeap::new(),
+        };
+        for i in 0..boxes.len() {
+            pairs.advance(i);
+        }
+        pairs
+    }
+
+    /// Put box `i`'s next closest neighbour in `heads`, finding another batch if it's run out
+    fn advance(&mut self, i: usize) {
+        let neighbours = &mut self.neighbours[i];
+        if neighbours.next.is_empty() && neighbours.batch > 0 {
+            let batch = self.tree.nearest(i, neighbours.batch, neighbours.furthest);
+            neighbours.batch = if batch.len() < neighbours.batch { 0 } else { 2 * batch.len() };
+            neighbours.furthest = batch.last().copied();
+            neighbours.next = batch;
+            neighbours.next.reverse();
+        }
+
+        if let Some((dist, j)) = neighbours.next.pop() {
+            self.heads.push(Reverse((dist, i.min(j), i.max(j), i)));
+        }
+    }
+}
+
+impl Iterator for PairsByDistance<'_> {
+    type Item = (usize, usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let Reverse((_, a, b, i)) = self.heads.pop()?;
+            self.advance(i);
+            if i == a {
+                return Some((a, b));
+            }
+        }
+    }
+}
+
+/// The circuits formed by connecting boxes, as a disjoint-set forest over box indices that keeps
+/// the size of each circuit as they're merged
+#[derive(Clone, Debug)]
+struct Circuits {
+    parent: Vec<usize>,
+    /// The number of boxes in each circuit, kept up to date only for each circuit's root
+    size: Vec<usize>,
+    count: usize,
+}
+
+impl Circuits {
+    /// Every box in a circuit of its own
+    fn new(boxes: usize) -> Self {
+        Self {
+            parent: (0..boxes).collect(),
+            size: vec![1; boxes],
+            count: boxes,
+        }
+    }
+
+    /// The root of the circuit containing `i`
+    fn find(&mut self, mut i: usize) -> usize {
+        while self.parent[i] != i {
+            // path halving
+            self.parent[i] = self.parent[self.parent[i]];
+            i = self.parent[i];
+        }
+        i
+    }
+
+    /// Connect boxes `a` and `b`, returning whether they were in different circuits
+    fn connect(&mut self, a: usize, b: usize) -> bool {
+        let (a, b) = (self.find(a), self.find(b));
+        if a == b {
+            return false;
+        }
+
+        let (small, large) = if self.size[a] < self.size[b] { (a, b) } else { (b, a) };
+        self.parent[small] = large;
+        self.size[large] += self.size[small];
+        self.count -= 1;
+        true
     }
 
-    fn distance_to(&self, other: &Self) -> f64 {
-        let sum = [self.0 - other.0, self.1 - other.1, self.2 - other.2]
+    /// The number of boxes in the circuit containing `i`
+    fn size_of(&mut self, i: usize) -> usize {
+        let root = self.find(i);
+        self.size[root]
+    }
+
+    /// How many circuits there are
+    fn count(&self) -> usize {
+        self.count
+    }
+
+    /// The number of boxes in each circuit
+    fn sizes(&self) -> impl Iterator<Item = usize> {
+        self.parent
             .iter()
-            .map(|n| n * n)
-            .sum::<i64>();
-        (sum as f64).sqrt()
+            .enumerate()
+            .filter(|&(i, &parent)| i == parent)
+            .map(|(i, _)| self.size[i])
     }
 }
 
+/// Convert a colour from HSV to RGB, with each of `hue`, `saturation` and `value` in `0.0..=1.0`
+fn hsv_to_rgb(hue: f64, saturation: f64, value: f64) -> [u8; 3] {
+    let channel = |n: f64| {
+        let k = (n + hue * 6.0) % 6.0;
+        let c = value - value * saturation * k.min(4.0 - k).clamp(0.0, 1.0);
+        (c * 255.0).round() as u8
+    };
+    [channel(5.0), channel(3.0), channel(1.0)]
+}
+
+pub const DAY: Day = Day::new::<Day08>(8, "Playground")
+    .with_params(&[ParamSpec::new(
+        "connections",
+        1000,
+        "how many of the closest pairs to connect in part 1",
+    )])
+    .with_tools(&[
+        Tool::new(
+            "ties",
+            "check whether pairs tied at part 1's cut-off could change its answer",
+        ),
+        Tool::new(
+            "sweep",
+            "CSV of the circuits after each connection, up to when they're all connected",
+        ),
+        Tool::new(
+            "dot",
+            "part 1's circuits as a Graphviz graph, coloured by circuit",
+        ),
+        Tool::new("obj", "part 1's circuits as a Wavefront OBJ mesh of points and lines"),
+        Tool::new("ply", "part 1's circuits as a PLY mesh, coloured by circuit"),
+    ]);
+
 #[derive(Debug)]
 pub struct Day08 {
     boxes: Vec<JBox>,
+    connections: usize,
 }
 
-impl Solution for Day08 {
-    fn with_input(input: String) -> Self {
-        let boxes = input.trim().lines().map(JBox::parse).collect_vec();
-        Self { boxes }
+impl Day08 {
+    /// Every pair of boxes, as indices into `boxes`, from closest to furthest apart
+    fn pairs_by_distance(&self) -> PairsByDistance<'_> {
+        PairsByDistance::new(&self.boxes)
     }
 
-    fn part1(&self) -> String {
-        let mut map = HashMap::with_capacity(self.boxes.len());
-        let mut graph = Graph::with_capacity(self.boxes.len(), 1000);
+    /// The pairs of the minimum spanning tree of the boxes, found with Borůvka's algorithm, as
+    /// `(distance, a, b)` with `a < b`
+    fn spanning_tree(&self) -> Vec<(i128, usize, usize)> {
+        let tree = KdTree::new(&self.boxes);
+        let mut circuits = Circuits::new(self.boxes.len());
+        let mut edges = vec![];
+
+        while circuits.count() > 1 {
+            let circuit = (0..self.boxes.len()).map(|i| circuits.find(i)).collect_vec();
+            let uniform = tree.uniform_circuits(&circuit);
 
-        for jbox in &self.boxes {
-            let node = graph.add_node(*jbox);
-            map.insert(*jbox, node);
+            // the closest pair leaving each circuit, indexed by the circuit's root
+            let mut closest = vec![None; self.boxes.len()];
+            for (i, &c) in circuit.iter().enumerate() {
+                tree.closest_elsewhere((0, 0), i, &circuit, &uniform, &mut closest[c]);
+            }
+
+            for (dist, a, b) in closest.into_iter().flatten() {
+                if circuits.connect(a, b) {
+                    edges.push((dist, a, b));
+                }
+            }
         }
 
-        let mut distances = Vec::with_capacity(499500);
-        distances.extend(
-            self.boxes
-                .iter()
-                .tuple_combinations()
-                .map(|(a, b)| (a.distance_to(b), a, b)),
-        );
+        edges
+    }
 
-        distances.sort_by(|(dist_a, _, _), (dist_b, _, _)| dist_a.partial_cmp(dist_b).unwrap());
+    /// The circuits formed by connecting the closest `connections` pairs, and those pairs
+    fn connect_closest(&self) -> (Circuits, Vec<(usize, usize)>) {
+        let mut circuits = Circuits::new(self.boxes.len());
+        let pairs = self.pairs_by_distance().take(self.connections).collect_vec();
+        for &(a, b) in &pairs {
+            circuits.connect(a, b);
+        }
+        (circuits, pairs)
+    }
+
+    /// A colour for each box, shared by every box in the same circuit, or grey for boxes on their
+    /// own
+    fn circuit_colours(circuits: &mut Circuits) -> Vec<[u8; 3]> {
+        let mut hues = HashMap::new();
+        (0..circuits.parent.len())
+            .map(|i| {
+                if circuits.size_of(i) == 1 {
+                    return [0xc0; 3];
+                }
+                let next = hues.len();
+                // hues a golden angle apart, so neighbouring circuits stand out
+                let hue = *hues.entry(circuits.find(i)).or_insert(next) as f64 * 0.618_034;
+                hsv_to_rgb(hue.fract(), 0.6, 0.95)
+            })
+            .collect()
+    }
 
-        for (_, a, b) in &distances[..1000] {
-            let node_a = map.get(a).unwrap();
-            let node_b = map.get(b).unwrap();
+    /// Write part 1's circuits as an undirected Graphviz graph
+    fn write_dot(&self, out: &mut dyn Write) -> std::io::Result<()> {
+        let (mut circuits, pairs) = self.connect_closest();
+        let colours = Self::circuit_colours(&mut circuits);
 
-            graph.add_edge(*node_a, *node_b, ());
+        writeln!(out, "graph circuits {{")?;
+        writeln!(out, "    node [style=filled];")?;
+        for (i, (JBox(x, y, z), [r, g, b])) in self.boxes.iter().zip(colours).enumerate() {
+            writeln!(
+                out,
+                "    {i} [label=\"{x},{y},{z}\", fillcolor=\"#{r:02x}{g:02x}{b:02x}\"];"
+            )?;
         }
+        for (a, b) in pairs {
+            writeln!(out, "    {a} -- {b};")?;
+        }
+        writeln!(out, "}}")
+    }
+
+    /// Write part 1's circuits as a Wavefront OBJ file, with a vertex for each box and a line for
+    /// each connection
+    fn write_obj(&self, out: &mut dyn Write) -> std::io::Result<()> {
+        let (_, pairs) = self.connect_closest();
 
-        kosaraju_scc(&graph)
-            .into_iter()
-            .map(|scc| scc.len())
-            .k_largest(3)
-            .product::<usize>()
-            .to_string()
+        writeln!(
+            out,
+            "# {} junction boxes, {} connections",
+            self.boxes.len(),
+            pairs.len()
+        )?;
+        for JBox(x, y, z) in &self.boxes {
+            writeln!(out, "v {x} {y} {z}")?;
+        }
+        // OBJ indices start at 1
+        for (a, b) in pairs {
+            writeln!(out, "l {} {}", a + 1, b + 1)?;
+        }
+        Ok(())
     }
 
-    fn part2(&self) -> String {
-        let mut map = HashMap::with_capacity(self.boxes.len());
-        let mut graph = Graph::with_capacity(self.boxes.len(), 1000);
+    /// Write part 1's circuits as an ASCII PLY file, with a coloured vertex for each box and an
+    /// edge for each connection
+    fn write_ply(&self, out: &mut dyn Write) -> std::io::Result<()> {
+        let (mut circuits, pairs) = self.connect_closest();
+        let colours = Self::circuit_colours(&mut circuits);
 
-        for jbox in &self.boxes {
-            let node = graph.add_node(*jbox);
-            map.insert(*jbox, node);
+        writeln!(out, "ply")?;
+        writeln!(out, "format ascii 1.0")?;
+        writeln!(out, "element vertex {}", self.boxes.len())?;
+        for property in ["int x", "int y", "int z"] {
+            writeln!(out, "property {property}")?;
+        }
+        for property in ["uchar red", "uchar green", "uchar blue"] {
+            writeln!(out, "property {property}")?;
+        }
+        writeln!(out, "element edge {}", pairs.len())?;
+        writeln!(out, "property int vertex1")?;
+        writeln!(out, "property int vertex2")?;
+        writeln!(out, "end_header")?;
+
+        for (JBox(x, y, z), [r, g, b]) in self.boxes.iter().zip(colours) {
+            writeln!(out, "{x} {y} {z} {r} {g} {b}")?;
+        }
+        for (a, b) in pairs {
+            writeln!(out, "{a} {b}")?;
         }
+        Ok(())
+    }
+
+    /// The product of the sizes of the three largest circuits
+    fn largest_circuits(circuits: &Circuits) -> usize {
+        circuits.sizes().k_largest(3).product()
+    }
 
-        let mut distances = Vec::with_capacity(499500);
-        distances.extend(
-            self.boxes
+    /// Write, as CSV, the number of circuits and the sizes of the three largest after each
+    /// connection, until every box is in one circuit
+    fn sweep(&self, out: &mut dyn Write) -> std::io::Result<()> {
+        let mut circuits = Circuits::new(self.boxes.len());
+        // how many circuits there are of each size
+        let mut sizes = BTreeMap::from([(1, self.boxes.len())]);
+
+        writeln!(out, "connections,circuits,largest,second,third")?;
+        for (i, (a, b)) in self.pairs_by_distance().enumerate() {
+            let merged = [circuits.size_of(a), circuits.size_of(b)];
+            if circuits.connect(a, b) {
+                for size in merged {
+                    let count = sizes.get_mut(&size).unwrap();
+                    *count -= 1;
+                    if *count == 0 {
+                        sizes.remove(&size);
+                    }
+                }
+                *sizes.entry(merged[0] + merged[1]).or_default() += 1;
+            }
+
+            let largest = sizes
                 .iter()
-                .tuple_combinations()
-                .map(|(a, b)| (a.distance_to(b), a, b)),
-        );
+                .rev()
+                .flat_map(|(&size, &count)| std::iter::repeat_n(size, count))
+                .take(3)
+                .collect_vec();
+            writeln!(
+                out,
+                "{},{},{}",
+                i + 1,
+                circuits.count(),
+                (0..3)
+                    .map(|i| largest.get(i).map_or(String::new(), usize::to_string))
+                    .join(",")
+            )?;
+
+            if circuits.count() == 1 {
+                break;
+            }
+        }
 
-        distances.sort_by(|(dist_a, _, _), (dist_b, _, _)| dist_a.partial_cmp(dist_b).unwrap());
+        Ok(())
+    }
 
-        for (_, a, b) in distances {
-            let node_a = map.get(a).unwrap();
-            let node_b = map.get(b).unwrap();
+    /// Write how many pairs are the same distance apart as the last one part 1 connects, and
+    /// every answer that connecting a different selection of them would give
+    fn report_ties(&self, out: &mut dyn Write) -> std::io::Result<()> {
+        /// The most selections of tied pairs worth trying
+        const MAX_SELECTIONS: usize = 100_000;
 
-            graph.add_edge(*node_a, *node_b, ());
+        let mut pairs = self.pairs_by_distance();
+        let connected = pairs.by_ref().take(self.connections).collect_vec();
+        let Some(&(a, b)) = connected.last() else {
+            return writeln!(out, "No pairs are connected");
+        };
+        let cut_off = self.boxes[a].squared_distance_to(&self.boxes[b]);
+        let is_tied = |&(a, b): &(usize, usize)| {
+            self.boxes[a].squared_distance_to(&self.boxes[b]) == cut_off
+        };
 
-            if connected_components(&graph) == 1 {
-                return (a.0 * b.0).to_string();
+        let mut circuits = Circuits::new(self.boxes.len());
+        let mut tied = vec![];
+        for pair @ &(a, b) in &connected {
+            if is_tied(pair) {
+                tied.push((a, b));
+            } else {
+                circuits.connect(a, b);
             }
         }
+        let taken = tied.len();
+        tied.extend(pairs.take_while(is_tied));
+
+        writeln!(
+            out,
+            "Pair {} is at a squared distance of {}",
+            connected.len(),
+            cut_off
+        )?;
+        writeln!(
+            out,
+            "{} pair(s) are tied at that distance, {taken} of them connected",
+            tied.len()
+        )?;
+        if taken == tied.len() {
+            return writeln!(out, "No ties are cut off, so the answer is certain");
+        }
+
+        let selections = (0..tied.len()).combinations(taken);
+        if selections.size_hint().1.is_none_or(|n| n > MAX_SELECTIONS) {
+            return writeln!(
+                out,
+                "Too many ways to choose which ties to connect to check them all"
+            );
+        }
+
+        let answers = selections
+            .map(|selection| {
+                let mut circuits = circuits.clone();
+                for i in selection {
+                    circuits.connect(tied[i].0, tied[i].1);
+                }
+                Self::largest_circuits(&circuits)
+            })
+            .sorted()
+            .dedup()
+            .collect_vec();
+        if let [answer] = answers[..] {
+            writeln!(out, "Every choice of ties gives the same answer: {answer}")
+        } else {
+            writeln!(
+                out,
+                "The choice of ties changes the answer, which could be any of: {}",
+                answers.iter().join(", ")
+            )
+        }
+    }
+}
+
+impl Solution for Day08 {
+    fn with_input(input: String, params: &Params) -> Result<Self, ParseError> {
+        let boxes = parse::parse_lines(&input, JBox::parse)?;
+        if boxes.len() < 2 {
+            return Err(ParseError::end_of_input(
+                &input,
+                "expected at least two junction boxes",
+            ));
+        }
+        Ok(Self {
+            boxes,
+            connections: params.get("connections"),
+        })
+    }
 
-        panic!(
-            "graph not connected, {} components",
-            connected_components(&graph)
-        );
+    fn part1(&self) -> Answer {
+        let (circuits, _) = self.connect_closest();
+        Self::largest_circuits(&circuits).into()
+    }
+
+    fn part2(&self) -> Answer {
+        // connecting pairs from closest to furthest, the last one that joins two circuits is the
+        // furthest apart pair in the spanning tree
+        let (_, a, b) = self.spanning_tree().into_iter().max().unwrap();
+        (self.boxes[a].0 * self.boxes[b].0).into()
+    }
+
+    fn run_tool(&self, name: &str, output: &mut ToolOutput) -> std::io::Result<()> {
+        match name {
+            "ties" => self.report_ties(output.out()?),
+            "sweep" => self.sweep(output.out()?),
+            "dot" => self.write_dot(output.out()?),
+            "obj" => self.write_obj(output.out()?),
+            "ply" => self.write_ply(output.out()?),
+            _ => Err(Tool::unknown(name)),
+        }
     }
 }
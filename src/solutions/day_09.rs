@@ -1,39 +1,207 @@
 use itertools::Itertools;
 
-use crate::solution::Solution;
+use crate::{
+    answer::Answer,
+    params::Params,
+    parse::{self, ParseError},
+    solution::{Day, Solution},
+};
 
 #[derive(Clone, Copy, Debug)]
 struct Tile(u64, u64);
 
 impl Tile {
-    fn parse(line: &str) -> Self {
-        let (x, y) = line.split_once(',').unwrap();
-        Self(x.parse().unwrap(), y.parse().unwrap())
+    fn parse(line: &str) -> Result<Self, ParseError> {
+        let Some((x, y)) = line.split_once(',') else {
+            return Err(ParseError::line(line, "expected coordinates like `7,1`"));
+        };
+        Ok(Self(
+            parse::parse_number(line, x)?,
+            parse::parse_number(line, y)?,
+        ))
     }
 
     fn area(&self, other: &Self) -> u64 {
         (self.0.abs_diff(other.0) + 1) * (self.1.abs_diff(other.1) + 1)
     }
+
+    fn shares_row_or_column(&self, other: &Self) -> bool {
+        self.0 == other.0 || self.1 == other.1
+    }
+}
+
+/// The tiles inside the loop, on a grid compressed to the red tiles' coordinates
+///
+/// Each distinct red x and y gets a column or row of its own, and each gap between two of them is
+/// squashed into a single column or row, since every tile in a gap is in or out of the loop
+/// together. Coordinates next to each other have no tiles between them, so no gap. A border of gap
+/// cells surrounds it all so the outside is connected.
+struct Floor {
+    columns: Axis,
+    rows: Axis,
+    /// How many cells are in or on the loop above and to the left of each cell, with an extra
+    /// leading row and column of zeros
+    inside_before: Vec<usize>,
+}
+
+impl Floor {
+    fn new(red_tiles: &[Tile]) -> Self {
+        let columns = Axis::new(red_tiles.iter().map(|t| t.0));
+        let rows = Axis::new(red_tiles.iter().map(|t| t.1));
+        let (width, height) = (columns.len, rows.len);
+
+        // `None` until the flood fill from the border reaches it, and `Some(false)` on the loop
+        let mut outside = vec![None; width * height];
+        for (a, b) in red_tiles.iter().circular_tuple_windows() {
+            let (ax, ay) = (columns.cell(a.0), rows.cell(a.1));
+            let (bx, by) = (columns.cell(b.0), rows.cell(b.1));
+            for y in ay.min(by)..=ay.max(by) {
+                for x in ax.min(bx)..=ax.max(bx) {
+                    outside[y * width + x] = Some(false);
+                }
+            }
+        }
+
+        let mut stack = vec![0];
+        outside[0] = Some(true);
+        while let Some(i) = stack.pop() {
+            let (x, y) = (i % width, i / width);
+            let neighbours = [
+                (x > 0).then(|| i - 1),
+                (x + 1 < width).then(|| i + 1),
+                (y > 0).then(|| i - width),
+                (y + 1 < height).then(|| i + width),
+            ];
+            for n in neighbours.into_iter().flatten() {
+                if outside[n].is_none() {
+                    outside[n] = Some(true);
+                    stack.push(n);
+                }
+            }
+        }
+
+        let mut inside_before = vec![0; (width + 1) * (height + 1)];
+        for y in 0..height {
+            for x in 0..width {
+                let inside = usize::from(outside[y * width + x] != Some(true));
+                inside_before[(y + 1) * (width + 1) + x + 1] = inside
+                    + inside_before[y * (width + 1) + x + 1]
+                    + inside_before[(y + 1) * (width + 1) + x]
+                    - inside_before[y * (width + 1) + x];
+            }
+        }
+
+        Self {
+            columns,
+            rows,
+            inside_before,
+        }
+    }
+
+    /// Whether every tile in the rectangle with corners `a` and `b` is in or on the loop
+    fn contains_rect(&self, a: &Tile, b: &Tile) -> bool {
+        let (ax, ay) = (self.columns.cell(a.0), self.rows.cell(a.1));
+        let (bx, by) = (self.columns.cell(b.0), self.rows.cell(b.1));
+        let (x0, x1) = (ax.min(bx), ax.max(bx) + 1);
+        let (y0, y1) = (ay.min(by), ay.max(by) + 1);
+
+        let stride = self.columns.len + 1;
+        let inside = self.inside_before[y1 * stride + x1] + self.inside_before[y0 * stride + x0]
+            - self.inside_before[y0 * stride + x1]
+            - self.inside_before[y1 * stride + x0];
+        inside == (x1 - x0) * (y1 - y0)
+    }
+}
+
+/// One axis of a [`Floor`]: which compressed column or row each red coordinate along it is in
+struct Axis {
+    coords: Vec<u64>,
+    cells: Vec<usize>,
+    /// How many columns or rows there are, including the border at each end
+    len: usize,
+}
+
+impl Axis {
+    fn new(coords: impl Iterator<Item = u64>) -> Self {
+        let coords = coords.sorted().dedup().collect_vec();
+
+        // cell 0 is the border
+        let mut next = 1;
+        let mut cells = Vec::with_capacity(coords.len());
+        for (i, &coord) in coords.iter().enumerate() {
+            if i > 0 && coord - coords[i - 1] > 1 {
+                // a gap of tiles between this coordinate and the last
+                next += 1;
+            }
+            cells.push(next);
+            next += 1;
+        }
+
+        Self {
+            coords,
+            cells,
+            len: next + 1,
+        }
+    }
+
+    /// The compressed column or row of `coord`, which must be a red tile's
+    fn cell(&self, coord: u64) -> usize {
+        self.cells[self.coords.binary_search(&coord).unwrap()]
+    }
 }
 
+pub const DAY: Day = Day::new::<Day09>(9, "Movie Theater");
+
 #[derive(Debug)]
 pub struct Day09 {
     red_tiles: Vec<Tile>,
 }
 
 impl Solution for Day09 {
-    fn with_input(input: String) -> Self {
-        let red_tiles = input.lines().map(Tile::parse).collect();
-        Self { red_tiles }
+    fn with_input(input: String, _params: &Params) -> Result<Self, ParseError> {
+        let red_tiles = parse::parse_lines(&input, Tile::parse)?;
+        if red_tiles.len() < 2 {
+            return Err(ParseError::end_of_input(
+                &input,
+                "expected at least two red tiles",
+            ));
+        }
+
+        let lines = input.trim_end().lines().collect_vec();
+        for (i, (a, b)) in red_tiles.iter().circular_tuple_windows().enumerate() {
+            if !a.shares_row_or_column(b) {
+                let line = (i + 1) % lines.len();
+                let message = if line == 0 {
+                    "not in the same row or column as the last red tile"
+                } else {
+                    "not in the same row or column as the previous red tile"
+                };
+                return Err(ParseError::line(lines[line], message).offset_lines(line));
+            }
+        }
+
+        Ok(Self { red_tiles })
+    }
+
+    fn part1(&self) -> Answer {
+        self.red_tiles
+            .iter()
+            .tuple_combinations()
+            .map(|(a, b)| a.area(b))
+            .max()
+            .unwrap()
+            .into()
     }
 
-    fn part1(&self) -> String {
+    fn part2(&self) -> Answer {
+        let floor = Floor::new(&self.red_tiles);
         self.red_tiles
             .iter()
             .tuple_combinations()
+            .filter(|(a, b)| floor.contains_rect(a, b))
             .map(|(a, b)| a.area(b))
             .max()
             .unwrap()
-            .to_string()
+            .into()
     }
 }
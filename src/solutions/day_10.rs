@@ -1,48 +1,115 @@
-use std::fmt::Debug;
+use std::{
+    fmt::{Debug, Display},
+    io::Write,
+};
 
-use arrayvec::ArrayVec;
 use itertools::Itertools;
 
-use crate::solution::Solution;
+use crate::{
+    answer::Answer,
+    params::Params,
+    parse::{self, ParseError},
+    solution::{Day, Solution, Tool, ToolOutput},
+};
+
+/// A set of indices below a fixed length, kept in a single word when the length is at most 64
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum BitSet {
+    Word(u64),
+    Words(Vec<u64>),
+}
 
-const MAX_LIGHTS: usize = 10;
-const MAX_BUTTONS: usize = 13;
+impl BitSet {
+    fn new(len: usize) -> Self {
+        if len <= 64 {
+            Self::Word(0)
+        } else {
+            Self::Words(vec![0; len.div_ceil(64)])
+        }
+    }
 
-#[derive(Clone, Default, PartialEq)]
-struct Lights(ArrayVec<bool, MAX_LIGHTS>);
+    fn insert(&mut self, i: usize) {
+        match self {
+            Self::Word(word) => *word |= 1 << i,
+            Self::Words(words) => words[i / 64] |= 1 << (i % 64),
+        }
+    }
 
-impl Lights {
-    fn new(size: usize) -> Self {
-        let mut lights = ArrayVec::new();
-        lights.extend(std::iter::repeat_n(false, size));
-        Self(lights)
+    fn contains(&self, i: usize) -> bool {
+        match self {
+            Self::Word(word) => word & 1 << i != 0,
+            Self::Words(words) => words[i / 64] & 1 << (i % 64) != 0,
+        }
+    }
+
+    /// Toggle every index in `other`, which must have the same length
+    fn toggle(&mut self, other: &Self) {
+        match (self, other) {
+            (Self::Word(a), Self::Word(b)) => *a ^= b,
+            (Self::Words(a), Self::Words(b)) => a.iter_mut().zip(b).for_each(|(a, b)| *a ^= b),
+            _ => unreachable!("bitsets of different lengths"),
+        }
     }
 
-    fn push_button(&mut self, b: &Button) {
-        for i in b.lights_activated() {
-            self.0[i] ^= true; // invert via xor
+    fn len(&self) -> u32 {
+        match self {
+            Self::Word(word) => word.count_ones(),
+            Self::Words(words) => words.iter().map(|w| w.count_ones()).sum(),
         }
     }
 }
 
+#[derive(Clone, Default, PartialEq)]
+struct Lights(Vec<bool>);
+
 impl Lights {
-    fn parse(s: &str) -> Self {
-        let lights = s
-            .strip_prefix('[')
-            .unwrap()
-            .strip_suffix(']')
-            .unwrap()
-            .chars()
-            .map(|b| match b {
+    /// Parse `s`, a slice of `line`
+    fn parse(line: &str, s: &str) -> Result<Self, ParseError> {
+        let inner = strip_delimiters(line, s, '[', ']')?;
+
+        let mut lights = Vec::new();
+        for (i, b) in inner.char_indices() {
+            let span = &inner[i..i + b.len_utf8()];
+            let light = match b {
                 '.' => false,
                 '#' => true,
-                _ => panic!("invalid light req: `{b}`"),
-            })
-            .collect();
+                _ => {
+                    return Err(ParseError::new(
+                        line,
+                        span,
+                        format!("invalid light req: `{b}`"),
+                    ));
+                }
+            };
+            lights.push(light);
+        }
+
+        Ok(Self(lights))
+    }
+}
+
+impl Lights {
+    /// The lights after pressing each of `pressed`
+    fn from_buttons<'a>(len: usize, pressed: impl IntoIterator<Item = &'a Button>) -> Self {
+        let mut lights = vec![false; len];
+        for light in pressed.into_iter().flat_map(Button::lights_activated) {
+            lights[light] ^= true;
+        }
         Self(lights)
     }
 }
 
+impl Display for Lights {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let lights = self
+            .0
+            .iter()
+            .map(|b| if *b { '#' } else { '.' })
+            .collect::<String>();
+        write!(f, "[{lights}]")
+    }
+}
+
 impl Debug for Lights {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let required_state = self
@@ -54,21 +121,45 @@ impl Debug for Lights {
     }
 }
 
+/// Strip `open` and `close` from either end of `s`, a slice of `line`
+fn strip_delimiters<'a>(
+    line: &str,
+    s: &'a str,
+    open: char,
+    close: char,
+) -> Result<&'a str, ParseError> {
+    s.strip_prefix(open)
+        .and_then(|s| s.strip_suffix(close))
+        .ok_or_else(|| ParseError::new(line, s, format!("expected `{open}...{close}`")))
+}
+
 #[derive(Clone, PartialEq)]
-struct Button(ArrayVec<usize, MAX_LIGHTS>);
+struct Button(Vec<usize>);
 
 impl Button {
-    fn parse(s: &str) -> Self {
-        let button = s
-            .strip_prefix('(')
-            .unwrap()
-            .strip_suffix(')')
-            .unwrap()
-            .split(',')
-            .map(|l| l.parse::<usize>().unwrap())
-            .collect();
+    /// Parse `s`, a slice of `line`, for a machine with `lights` lights
+    fn parse(line: &str, s: &str, lights: usize) -> Result<Self, ParseError> {
+        let mut button = Vec::new();
+        for light in strip_delimiters(line, s, '(', ')')?.split(',') {
+            let index = parse::parse_number(line, light)?;
+            if index >= lights {
+                return Err(ParseError::new(
+                    line,
+                    light,
+                    format!("light {index} is outside the diagram of {lights} lights"),
+                ));
+            }
+            if button.contains(&index) {
+                return Err(ParseError::new(
+                    line,
+                    light,
+                    format!("light {index} is already toggled by this button"),
+                ));
+            }
+            button.push(index);
+        }
 
-        Self(button)
+        Ok(Self(button))
     }
 
     fn lights_activated(&self) -> impl Iterator<Item = usize> {
@@ -76,6 +167,12 @@ impl Button {
     }
 }
 
+impl Display for Button {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "({})", self.0.iter().join(","))
+    }
+}
+
 impl Debug for Button {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let mut d = f.debug_tuple("Button");
@@ -89,214 +186,673 @@ impl Debug for Button {
 #[derive(Clone, Debug)]
 struct Machine {
     required_state: Lights,
-    buttons: ArrayVec<Button, MAX_BUTTONS>,
-    light_button_map: LightToButtonMap,
-    #[allow(unused)]
+    buttons: Vec<Button>,
     joltage_reqs: Vec<u16>,
+    toggles: Result<ToggleSystem, Unsolvable>,
+    joltage: Result<JoltageSystem, Unsolvable>,
 }
 
 impl Machine {
-    fn parse(line: &str) -> Self {
+    fn parse(line: &str) -> Result<Self, ParseError> {
         let mut tokens = line.split_whitespace().peekable();
 
-        let required_state = Lights::parse(tokens.next().unwrap());
+        let Some(required_state) = tokens.next() else {
+            return Err(ParseError::line(line, "expected a machine"));
+        };
+        let required_state = Lights::parse(line, required_state)?;
 
-        let buttons = tokens
+        let buttons: Vec<_> = tokens
             .peeking_take_while(|t| t.starts_with('('))
-            .map(Button::parse)
-            .collect::<ArrayVec<_, _>>();
-
-        let light_button_map = LightToButtonMap::from_buttons(required_state.0.len(), &buttons);
-
-        let joltage_reqs = tokens
-            .next()
-            .unwrap()
-            .strip_prefix('{')
-            .unwrap()
-            .strip_suffix('}')
-            .unwrap()
+            .map(|token| Button::parse(line, token, required_state.0.len()))
+            .collect::<Result<_, _>>()?;
+        let Some(joltage_reqs) = tokens.next() else {
+            let end = &line[line.len()..];
+            return Err(ParseError::new(line, end, "expected joltage requirements"));
+        };
+        let joltage_span = joltage_reqs;
+        let joltage_reqs = strip_delimiters(line, joltage_reqs, '{', '}')?
             .split(',')
-            .map(|n| n.parse().unwrap())
-            .collect();
+            .map(|n| parse::parse_number(line, n))
+            .collect::<Result<Vec<_>, _>>()?;
+        if joltage_reqs.len() != required_state.0.len() {
+            return Err(ParseError::new(
+                line,
+                joltage_span,
+                format!(
+                    "expected {} joltage requirements, one per light",
+                    required_state.0.len()
+                ),
+            ));
+        }
+
+        if let Some(extra) = tokens.next() {
+            return Err(ParseError::new(line, extra, "unexpected token after joltage requirements"));
+        }
 
-        Self {
+        let toggles = ToggleSystem::new(&required_state, &buttons);
+        let joltage = JoltageSystem::new(&buttons, &joltage_reqs);
+
+        Ok(Self {
             required_state,
             buttons,
-            light_button_map,
             joltage_reqs,
-        }
+            toggles,
+            joltage,
+        })
     }
 }
 
 impl Machine {
-    fn smallest_button_set(&self) -> usize {
-        let state = AlgState::new(self.required_state.0.len(), &self.buttons);
-        self.smallest_button_set_from_light(0, state).unwrap()
+    fn smallest_button_set(&self) -> Result<BitSet, Unsolvable> {
+        Ok(self.toggles.as_ref().map_err(|e| *e)?.fewest_buttons())
     }
 
-    fn smallest_button_set_from_light(&self, light: usize, state: AlgState) -> Option<usize> {
-        let matches_required_state = state.lights.0[light] == self.required_state.0[light];
-        let possible_buttons = self.valid_buttons_for_light(light, &state.buttons.0);
-
-        let mut smallest_found = None;
+    /// How many times to press each button to reach the joltage requirements in the fewest
+    /// presses, which is unreachable if the only solutions press some button a negative or
+    /// fractional number of times
+    fn fewest_joltage_presses(&self) -> Result<Vec<i64>, Unsolvable> {
+        self.joltage
+            .as_ref()
+            .map_err(|e| *e)?
+            .fewest_presses()
+            .ok_or(Unsolvable::Unreachable)
+    }
 
-        let set_sizes = if matches_required_state {
-            (0..=possible_buttons.len()).step_by(2)
-        } else {
-            (1..=possible_buttons.len()).step_by(2)
+    /// Write which buttons each part presses, and what they produce, so the answers can be
+    /// checked by hand, returning each part's presses if it was solved
+    fn explain(&self, out: &mut dyn Write) -> std::io::Result<(Option<usize>, Option<i64>)> {
+        let lights = match self.smallest_button_set() {
+            Ok(pressed) => Some(self.explain_lights(out, &pressed)?),
+            Err(e) => {
+                writeln!(out, "  lights:  unsolved, {e}")?;
+                None
+            }
         };
-        for pressed_button_set in
-            set_sizes.flat_map(|k| possible_buttons.iter().copied().combinations(k))
-        {
-            // pressing more buttons won't do anything
-            if smallest_found.is_some_and(|smallest_found| {
-                state.num_pressed + pressed_button_set.len() >= smallest_found
-            }) {
-                break;
+        let joltage = match self.fewest_joltage_presses() {
+            Ok(presses) => Some(self.explain_joltage(out, &presses)?),
+            Err(e) => {
+                writeln!(out, "  joltage: unsolved, {e}")?;
+                None
             }
+        };
+        Ok((lights, joltage))
+    }
 
-            let mut new_state = state.clone();
+    fn explain_lights(&self, out: &mut dyn Write, pressed: &BitSet) -> std::io::Result<usize> {
+        let pressed = self
+            .buttons
+            .iter()
+            .enumerate()
+            .filter(|&(i, _)| pressed.contains(i))
+            .map(|(_, b)| b)
+            .collect_vec();
+        let lights = Lights::from_buttons(self.required_state.0.len(), pressed.iter().copied());
+        writeln!(
+            out,
+            "  lights:  press {} -> {lights}, {} presses",
+            pressed.iter().join(" "),
+            pressed.len()
+        )?;
+        Ok(pressed.len())
+    }
 
-            for button_index in pressed_button_set.iter().copied() {
-                let button = &self.buttons[button_index];
-                new_state.press_button(button_index, button);
+    fn explain_joltage(&self, out: &mut dyn Write, presses: &[i64]) -> std::io::Result<i64> {
+        let mut counters = vec![0; self.joltage_reqs.len()];
+        for (button, &n) in self.buttons.iter().zip(presses) {
+            for counter in button.lights_activated() {
+                counters[counter] += n;
             }
+        }
+        let total = presses.iter().sum::<i64>();
+        writeln!(
+            out,
+            "  joltage: press {} -> {{{}}}, {total} presses",
+            self.buttons
+                .iter()
+                .zip(presses)
+                .filter(|&(_, &n)| n > 0)
+                .map(|(b, n)| format!("{b}x{n}"))
+                .join(" "),
+            counters.iter().join(","),
+        )?;
+        Ok(total)
+    }
+}
 
-            if let Some(count) = new_state.is_solution(&self.required_state) {
-                if count == 1 {
-                    return Some(1);
-                }
+/// The most combinations of free buttons [`ToggleSystem::fewest_buttons`] will try, which takes
+/// about a second; machines that could need more are left unsolved
+const MAX_COMBINATIONS: u64 = 1 << 24;
 
-                smallest_found = smallest_found.map(|s| s.min(count)).or(Some(count));
+/// The buttons pressed to reach a machine's required lights, as a linear system over GF(2)
+#[derive(Clone, Debug)]
+struct ToggleSystem {
+    /// The buttons pressed when none of the free buttons are
+    particular: BitSet,
+    /// A solution found by toggling in one vector of the null space at a time while that leaves
+    /// fewer buttons pressed, which bounds the search for the fewest
+    greedy: BitSet,
+    /// For each free button, the buttons that pressing it toggles in the solution
+    null_space: Vec<BitSet>,
+}
 
-                continue;
-            }
+impl ToggleSystem {
+    /// The system for a machine with `buttons`, unless none of them reach `required_state` or
+    /// there are too many combinations to search
+    fn new(required_state: &Lights, buttons: &[Button]) -> Result<Self, Unsolvable> {
+        let len = buttons.len();
 
-            if light >= self.required_state.0.len() {
+        // each row is the buttons that toggle a light, and whether it needs to end up on
+        let mut rows = required_state
+            .0
+            .iter()
+            .enumerate()
+            .map(|(light, &on)| {
+                let mut toggles = BitSet::new(len);
+                for (i, b) in buttons.iter().enumerate() {
+                    if b.0.contains(&light) {
+                        toggles.insert(i);
+                    }
+                }
+                (toggles, on)
+            })
+            .collect_vec();
+
+        let mut pivots = vec![];
+        let mut free = vec![];
+        for button in 0..len {
+            let r = pivots.len();
+            let Some(pivot_row) = (r..rows.len()).find(|&i| rows[i].0.contains(button)) else {
+                free.push(button);
                 continue;
+            };
+            rows.swap(r, pivot_row);
+
+            let (pivot, pivot_on) = rows[r].clone();
+            for (_, (toggles, on)) in rows.iter_mut().enumerate().filter(|&(i, _)| i != r) {
+                if toggles.contains(button) {
+                    toggles.toggle(&pivot);
+                    *on ^= pivot_on;
+                }
             }
+            pivots.push(button);
+        }
 
-            new_state.num_pressed += pressed_button_set.len();
-            for excluded_button_index in possible_buttons
-                .iter()
-                .filter(|b| !pressed_button_set.contains(b))
-            {
-                new_state.buttons.0[*excluded_button_index] = ButtonState::Unpressed;
-            }
+        // the remaining rows have had every button eliminated, so need the light to stay off
+        if rows[pivots.len()..].iter().any(|&(_, on)| on) {
+            return Err(Unsolvable::Unreachable);
+        }
 
-            // check the next light with the new state
-            if let Some(count) = self.smallest_button_set_from_light(light + 1, new_state) {
-                smallest_found = smallest_found.map(|s| s.min(count)).or(Some(count));
-            }
+        let mut particular = BitSet::new(len);
+        for (_, &pivot) in rows.iter().zip(&pivots).filter(|((_, on), _)| *on) {
+            particular.insert(pivot);
         }
+        let null_space: Vec<_> = free
+            .iter()
+            .map(|&button| {
+                let mut v = BitSet::new(len);
+                v.insert(button);
+                for ((toggles, _), &pivot) in rows.iter().zip(&pivots) {
+                    if toggles.contains(button) {
+                        v.insert(pivot);
+                    }
+                }
+                v
+            })
+            .collect();
 
-        smallest_found
+        let mut greedy = particular.clone();
+        while let Some(better) = null_space
+            .iter()
+            .map(|v| {
+                let mut solution = greedy.clone();
+                solution.toggle(v);
+                solution
+            })
+            .find(|solution| solution.len() < greedy.len())
+        {
+            greedy = better;
+        }
+
+        let system = Self {
+            particular,
+            greedy,
+            null_space,
+        };
+        if system.combinations() > MAX_COMBINATIONS {
+            return Err(Unsolvable::TooLarge);
+        }
+        Ok(system)
     }
 
-    fn valid_buttons_for_light(&self, light: usize, button_states: &[ButtonState]) -> Vec<usize> {
-        self.light_button_map
-            .buttons_for_light(light)
-            .iter()
-            .copied()
-            .filter(|b| button_states[*b] == ButtonState::Unknown)
-            .collect()
+    /// The most combinations of the null space [`Self::fewest_buttons`] could try before it stops
+    fn combinations(&self) -> u64 {
+        let free = self.null_space.len() as u64;
+        let largest = free.min(u64::from(self.greedy.len().saturating_sub(1)));
+
+        let mut total = 0u64;
+        let mut choose = 1u64;
+        for k in 1..=largest {
+            // C(free, k) from C(free, k - 1)
+            let Some(next) = choose.checked_mul(free - k + 1) else {
+                return u64::MAX;
+            };
+            choose = next / k;
+            total = total.saturating_add(choose);
+        }
+        total
+    }
+
+    /// The solution with the fewest buttons, trying combinations of the null space with the
+    /// fewest free buttons first
+    fn fewest_buttons(&self) -> BitSet {
+        let mut fewest = self.greedy.clone();
+        for k in 1..=self.null_space.len() {
+            for combination in self.null_space.iter().combinations(k) {
+                // every free button in a combination stays pressed, so `k` of them can't do better
+                if fewest.len() as usize <= k {
+                    return fewest;
+                }
+
+                let mut solution = self.particular.clone();
+                for v in combination {
+                    solution.toggle(v);
+                }
+                if solution.len() < fewest.len() {
+                    fewest = solution;
+                }
+            }
+        }
+        fewest
+    }
+}
+
+/// Why a machine's lights or joltage requirements were left unsolved
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Unsolvable {
+    /// No presses of the buttons reach the requirements
+    Unreachable,
+    /// Too many combinations to search, or numbers too big for an `i128` in the elimination
+    TooLarge,
+}
+
+impl Display for Unsolvable {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Unreachable => write!(f, "no combination of buttons reaches the requirements"),
+            Self::TooLarge => write!(f, "too many buttons to search for the fewest presses"),
+        }
     }
 }
 
+/// The presses of each button that reach a machine's joltage requirements, as a linear system in
+/// reduced row echelon form
 #[derive(Clone, Debug)]
-struct AlgState {
-    lights: Lights,
-    buttons: ButtonList,
-    num_pressed: usize,
+struct JoltageSystem {
+    /// Each row's coefficient for every button, followed by its target
+    rows: Vec<Vec<i128>>,
+    /// The button each row solves for, with a positive coefficient
+    pivots: Vec<usize>,
+    /// The buttons no row solves for, whose presses are searched over
+    free: Vec<usize>,
+    /// The counters each button adds to
+    counters: Vec<Vec<usize>>,
+    /// What each counter needs to reach
+    joltage_reqs: Vec<i64>,
 }
 
-impl AlgState {
-    fn new(light_count: usize, buttons: &[Button]) -> Self {
-        let lights = Lights::new(light_count);
-        let buttons = ButtonList::new(buttons.len());
+impl JoltageSystem {
+    /// The system for a machine with `buttons` and `joltage_reqs`
+    fn new(buttons: &[Button], joltage_reqs: &[u16]) -> Result<Self, Unsolvable> {
+        let mut rows = joltage_reqs
+            .iter()
+            .enumerate()
+            .map(|(counter, &req)| {
+                let mut row = buttons
+                    .iter()
+                    .map(|b| i128::from(b.0.contains(&counter)))
+                    .collect_vec();
+                row.push(i128::from(req));
+                row
+            })
+            .collect_vec();
+
+        let mut pivots = vec![];
+        let mut free = vec![];
+        // fraction-free (Bareiss) elimination: dividing each update by the previous pivot is always
+        // exact, and keeps every entry a minor of the original matrix rather than growing
+        let mut previous = 1;
+        for col in 0..buttons.len() {
+            let r = pivots.len();
+            let Some(pivot_row) = (r..rows.len()).find(|&i| rows[i][col] != 0) else {
+                free.push(col);
+                continue;
+            };
+            rows.swap(r, pivot_row);
+
+            let pivot_row = rows[r].clone();
+            let a = pivot_row[col];
+            for (_, row) in rows.iter_mut().enumerate().filter(|&(i, _)| i != r) {
+                let b = row[col];
+                for (x, &p) in row.iter_mut().zip(&pivot_row) {
+                    *x = x
+                        .checked_mul(a)
+                        .zip(p.checked_mul(b))
+                        .and_then(|(xa, pb)| xa.checked_sub(pb))
+                        .ok_or(Unsolvable::TooLarge)?
+                        / previous;
+                }
+            }
+            previous = a;
+            pivots.push(col);
+        }
 
-        Self {
-            lights,
-            buttons,
-            num_pressed: 0,
+        // the remaining rows have had every coefficient eliminated, so need a target of 0
+        if rows[pivots.len()..].iter().any(|row| row[buttons.len()] != 0) {
+            return Err(Unsolvable::Unreachable);
         }
+        rows.truncate(pivots.len());
+
+        // every press is applied to each row as `coefficient * presses`, summed over the buttons,
+        // so coefficients past this could overflow the search
+        let largest = i128::MAX / (i128::from(u16::MAX) + 1) / (buttons.len() as i128 + 1);
+        for (row, &pivot) in rows.iter_mut().zip(&pivots) {
+            let sign = row[pivot].signum();
+            let divisor = row.iter().fold(0, |d, &x| gcd(d, x.abs())) * sign;
+            row.iter_mut().for_each(|x| *x /= divisor);
+            if row.iter().any(|x| x.abs() > largest) {
+                return Err(Unsolvable::TooLarge);
+            }
+        }
+
+        Ok(Self {
+            rows,
+            pivots,
+            free,
+            counters: buttons.iter().map(|b| b.0.clone()).collect(),
+            joltage_reqs: joltage_reqs.iter().map(|&req| i64::from(req)).collect(),
+        })
     }
 
-    fn press_button(&mut self, idx: usize, button: &Button) {
-        self.lights.push_button(button);
-        self.buttons.0[idx] = ButtonState::Pressed;
+    /// The fewest total presses that solve the system, searching over the presses of each free
+    /// button in turn
+    fn fewest_presses(&self) -> Option<Vec<i64>> {
+        let mut search = JoltageSearch {
+            remaining: self.joltage_reqs.clone(),
+            targets: self.rows.iter().map(|row| row[self.counters.len()]).collect(),
+            pressed: 0,
+            presses: vec![0; self.counters.len()],
+            fewest: None,
+        };
+        self.search(&mut search, 0);
+        search.fewest.map(|(_, presses)| presses)
     }
 
-    fn is_solution(&self, required_state: &Lights) -> Option<usize> {
-        if self.lights == *required_state {
-            Some(
-                self.buttons
-                    .0
-                    .iter()
-                    .filter(|b| **b == ButtonState::Pressed)
-                    .count(),
-            )
-        } else {
-            None
+    fn search(&self, search: &mut JoltageSearch, i: usize) {
+        let Some(&button) = self.free.get(i) else {
+            if let Some(total) = self.solve(search)
+                && search.fewest.as_ref().is_none_or(|(f, _)| total < *f)
+            {
+                search.fewest = Some((total, search.presses.clone()));
+            }
+            return;
+        };
+
+        if !self.feasible(search, i) {
+            return;
+        }
+
+        // pressing buttons fractional numbers of times can only do better than whole presses, so
+        // if even that can't beat the best so far, nothing from here on will
+        let unfixed = self
+            .pivots
+            .iter()
+            .chain(&self.free[i..])
+            .map(|&button| self.counters[button].as_slice())
+            .collect_vec();
+        let Some(relaxed) = relaxed_fewest_presses(&unfixed, &search.remaining) else {
+            return;
+        };
+        if search
+            .fewest
+            .as_ref()
+            .is_some_and(|&(f, _)| search.pressed as f64 + relaxed > (f - 1) as f64 + EPSILON)
+        {
+            return;
+        }
+
+        for presses in 0..=self.max_presses(button, &search.remaining) {
+            search.press(self, button, presses);
+            self.search(search, i + 1);
+            search.press(self, button, -presses);
         }
     }
-}
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
-enum ButtonState {
-    Pressed,
-    Unpressed,
-    #[default]
-    Unknown,
+    /// How many more times `button` can be pressed before one of its counters overshoots
+    fn max_presses(&self, button: usize, remaining: &[i64]) -> i64 {
+        self.counters[button]
+            .iter()
+            .map(|&counter| remaining[counter])
+            .min()
+            .unwrap_or(0)
+    }
+
+    /// Whether every pivot button could still need a non-negative number of presses that doesn't
+    /// overshoot its counters, given the most the free buttons from `i` on can be pressed
+    fn feasible(&self, search: &JoltageSearch, i: usize) -> bool {
+        let max_free = self.free[i..]
+            .iter()
+            .map(|&button| (button, self.max_presses(button, &search.remaining)))
+            .collect_vec();
+
+        self.rows
+            .iter()
+            .zip(&self.pivots)
+            .zip(&search.targets)
+            .all(|((row, &pivot), &target)| {
+                let (mut lowest, mut highest) = (target, target);
+                for &(button, max) in &max_free {
+                    match row[button] {
+                        c if c > 0 => lowest -= c * i128::from(max),
+                        c => highest -= c * i128::from(max),
+                    }
+                }
+                let most = i128::from(self.max_presses(pivot, &search.remaining));
+                highest >= 0 && lowest <= row[pivot] * most
+            })
+    }
+
+    /// The total presses once every free button is fixed, if every pivot button then needs a
+    /// whole, non-negative number of presses, filling them in to `search.presses`
+    fn solve(&self, search: &mut JoltageSearch) -> Option<i64> {
+        let mut total = search.pressed;
+        for ((row, &pivot), &target) in self.rows.iter().zip(&self.pivots).zip(&search.targets) {
+            if target < 0 || target % row[pivot] != 0 {
+                return None;
+            }
+            // at most the pivot's smallest requirement, which fits in an `i64`
+            search.presses[pivot] = (target / row[pivot]) as i64;
+            total += search.presses[pivot];
+        }
+
+        Some(total)
+    }
 }
 
-#[derive(Clone, Debug)]
-struct ButtonList(ArrayVec<ButtonState, MAX_BUTTONS>);
+/// The state of [`JoltageSystem::search`]
+struct JoltageSearch {
+    /// How far each counter is from its requirement after the free buttons fixed so far
+    remaining: Vec<i64>,
+    /// What each row needs its pivot button to make up after the free buttons fixed so far
+    targets: Vec<i128>,
+    /// How many times the free buttons fixed so far are pressed
+    pressed: i64,
+    /// The presses of every button being tried
+    presses: Vec<i64>,
+    /// The fewest total presses found so far, and the presses of each button that gave it
+    fewest: Option<(i64, Vec<i64>)>,
+}
 
-impl ButtonList {
-    fn new(button_count: usize) -> Self {
-        let mut state = ArrayVec::new();
-        state.extend(std::iter::repeat_n(ButtonState::default(), button_count));
-        Self(state)
+impl JoltageSearch {
+    /// Press the free `button` `n` more times, or take back presses if `n` is negative
+    fn press(&mut self, system: &JoltageSystem, button: usize, n: i64) {
+        for &counter in &system.counters[button] {
+            self.remaining[counter] -= n;
+        }
+        for (target, row) in self.targets.iter_mut().zip(&system.rows) {
+            *target -= row[button] * i128::from(n);
+        }
+        self.pressed += n;
+        self.presses[button] += n;
     }
 }
 
-#[derive(Clone, Debug)]
-struct LightToButtonMap(ArrayVec<ArrayVec<usize, MAX_BUTTONS>, MAX_LIGHTS>);
+/// How close two floats have to be to count as equal in [`relaxed_fewest_presses`]
+const EPSILON: f64 = 1e-9;
 
-impl LightToButtonMap {
-    fn from_buttons(light_count: usize, buttons: &[Button]) -> Self {
-        let mut map = ArrayVec::new();
-        map.extend(std::iter::repeat_n(ArrayVec::default(), light_count));
+/// The fewest presses of `buttons`, given as the counters each adds to, that reach `remaining` if
+/// buttons could be pressed fractional numbers of times, or `None` if no presses reach it at all
+fn relaxed_fewest_presses(buttons: &[&[usize]], remaining: &[i64]) -> Option<f64> {
+    let (counters, n) = (remaining.len(), buttons.len());
+    let rhs = n + counters;
 
-        for (i, b) in buttons.iter().enumerate() {
-            map[b.0[0]].push(i);
+    let mut rows = vec![vec![0.0; rhs + 1]; counters];
+    for (button, touched) in buttons.iter().enumerate() {
+        for &counter in *touched {
+            rows[counter][button] = 1.0;
         }
+    }
+    for (counter, row) in rows.iter_mut().enumerate() {
+        row[n + counter] = 1.0;
+        row[rhs] = remaining[counter] as f64;
+    }
+    let mut basis = (n..rhs).collect_vec();
+
+    // two-phase simplex: an artificial column per counter starts out making up its requirement,
+    // and the first phase drives them to 0
+    let artificial = (0..rhs).map(|col| f64::from(u8::from(col >= n))).collect_vec();
+    if simplex(&mut rows, &mut basis, &artificial, rhs) > EPSILON {
+        return None;
+    }
+
+    // swap any artificial columns still in the basis, at 0, for a button, so the second phase
+    // can't bring them back up; a row with no button left in it is redundant
+    for r in 0..counters {
+        if basis[r] >= n
+            && let Some(col) = (0..n).find(|&col| rows[r][col].abs() > EPSILON)
+        {
+            pivot(&mut rows, &mut basis, r, col);
+        }
+    }
+
+    let presses = (0..rhs).map(|col| f64::from(u8::from(col < n))).collect_vec();
+    Some(simplex(&mut rows, &mut basis, &presses, n))
+}
+
+/// Minimise `costs` over the tableau `rows` by Bland's rule, only bringing the first `enter`
+/// columns into the basis, returning the minimum
+fn simplex(rows: &mut [Vec<f64>], basis: &mut [usize], costs: &[f64], enter: usize) -> f64 {
+    let rhs = costs.len();
+    loop {
+        let reduced = |col: usize| {
+            costs[col]
+                - rows
+                    .iter()
+                    .zip(&*basis)
+                    .map(|(row, &b)| costs[b] * row[col])
+                    .sum::<f64>()
+        };
+        let Some(col) = (0..enter).find(|&col| reduced(col) < -EPSILON) else {
+            return rows.iter().zip(&*basis).map(|(row, &b)| costs[b] * row[rhs]).sum();
+        };
 
-        Self(map)
+        // the objective is bounded below by 0, so some row always limits the entering column
+        let r = (0..rows.len())
+            .filter(|&r| rows[r][col] > EPSILON)
+            .min_by(|&a, &b| {
+                let (ra, rb) = (rows[a][rhs] / rows[a][col], rows[b][rhs] / rows[b][col]);
+                ra.total_cmp(&rb).then(basis[a].cmp(&basis[b]))
+            })
+            .expect("the objective is bounded below");
+        pivot(rows, basis, r, col);
     }
+}
 
-    fn buttons_for_light(&self, light: usize) -> &[usize] {
-        &self.0[light]
+/// Bring `col` into the basis in place of row `r`'s column
+fn pivot(rows: &mut [Vec<f64>], basis: &mut [usize], r: usize, col: usize) {
+    let divisor = rows[r][col];
+    rows[r].iter_mut().for_each(|x| *x /= divisor);
+    let pivot_row = rows[r].clone();
+    for (_, row) in rows.iter_mut().enumerate().filter(|&(i, _)| i != r) {
+        let factor = row[col];
+        if factor != 0.0 {
+            for (x, p) in row.iter_mut().zip(&pivot_row) {
+                *x -= factor * p;
+            }
+        }
     }
+    basis[r] = col;
 }
 
+fn gcd(a: i128, b: i128) -> i128 {
+    if b == 0 { a } else { gcd(b, a % b) }
+}
+
+pub const DAY: Day = Day::new::<Day10>(10, "Factory").with_tools(&[Tool::new(
+    "explain",
+    "show the buttons each part presses for every machine",
+)]);
+
 #[derive(Debug)]
 pub struct Day10 {
     machines: Vec<Machine>,
 }
 
+impl Day10 {
+    /// Write each machine's presses for both parts, and the totals they add up to
+    fn explain(&self, out: &mut dyn Write) -> std::io::Result<()> {
+        let (mut lights, mut joltage) = (Some(0), Some(0));
+        for (i, machine) in self.machines.iter().enumerate() {
+            writeln!(out, "Machine {}: {}", i + 1, machine.required_state)?;
+            let (l, j) = machine.explain(out)?;
+            lights = lights.zip(l).map(|(a, b)| a + b);
+            joltage = joltage.zip(j).map(|(a, b)| a + b);
+        }
+        let total = |n: Option<String>| n.unwrap_or_else(|| "unsolved".to_string());
+        writeln!(
+            out,
+            "Total: {} presses for the lights, {} for the joltage",
+            total(lights.map(|n| n.to_string())),
+            total(joltage.map(|n| n.to_string())),
+        )
+    }
+}
+
 impl Solution for Day10 {
-    fn with_input(input: String) -> Self {
-        let machines = input.lines().map(Machine::parse).collect();
-        Self { machines }
+    fn with_input(input: String, _params: &Params) -> Result<Self, ParseError> {
+        let machines = parse::parse_lines(&input, Machine::parse)?;
+        Ok(Self { machines })
     }
 
-    fn part1(&self) -> String {
+    fn part1(&self) -> Answer {
         self.machines
             .iter()
-            .map(Machine::smallest_button_set)
-            .sum::<usize>()
-            .to_string()
+            .map(|machine| Some(machine.smallest_button_set().ok()?.len()))
+            .sum::<Option<u32>>()
+            .map_or(Answer::Unsolved, Answer::from)
+    }
+
+    fn part2(&self) -> Answer {
+        self.machines
+            .iter()
+            .map(|machine| Some(machine.fewest_joltage_presses().ok()?.iter().sum::<i64>()))
+            .sum::<Option<i64>>()
+            .map_or(Answer::Unsolved, Answer::from)
+    }
+
+    fn run_tool(&self, name: &str, output: &mut ToolOutput) -> std::io::Result<()> {
+        match name {
+            "explain" => self.explain(output.out()?),
+            _ => Err(Tool::unknown(name)),
+        }
     }
 }
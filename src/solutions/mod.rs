@@ -1,21 +1,44 @@
-mod day_01;
-mod day_02;
-mod day_03;
-mod day_04;
-mod day_05;
-mod day_06;
-mod day_07;
-mod day_08;
-mod day_09;
-mod day_10;
+use crate::solution::Day;
 
-pub use day_01::Day01;
-pub use day_02::Day02;
-pub use day_03::Day03;
-pub use day_04::Day04;
-pub use day_05::Day05;
-pub use day_06::Day06;
-pub use day_07::Day07;
-pub use day_08::Day08;
-pub use day_09::Day09;
-pub use day_10::Day10;
+/// Declare each day module and collect its `DAY` entry into [`REGISTRY`]
+///
+/// Declaring a module here is the only way to compile it, so a day can't be added without being
+/// registered. Days must be listed in increasing order, which also rules out registering the same
+/// day twice; both are checked at compile time.
+macro_rules! registry {
+    ($($module:ident),* $(,)?) => {
+        $(mod $module;)*
+
+        /// Every registered day, in order
+        pub const REGISTRY: &[Day] = &[$($module::DAY),*];
+    };
+}
+
+registry! {
+    day_01,
+    day_02,
+    day_03,
+    day_04,
+    day_05,
+    day_06,
+    day_07,
+    day_08,
+    day_09,
+    day_10,
+}
+
+const _: () = {
+    let mut i = 1;
+    while i < REGISTRY.len() {
+        assert!(
+            REGISTRY[i - 1].number < REGISTRY[i].number,
+            "days must be registered once each, in increasing order"
+        );
+        i += 1;
+    }
+};
+
+/// Look up a registered day by its number
+pub fn get(day: u32) -> Option<&'static Day> {
+    REGISTRY.iter().find(|d| d.number == day)
+}
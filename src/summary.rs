@@ -0,0 +1,119 @@
+use std::time::Duration;
+
+use aoc_2025::answer::Answer;
+
+use crate::{answers::Status, run::DayRun};
+
+const HEADER: [&str; 9] = [
+    "Day", "Parse", "Part 1", "Status", "Time", "Part 2", "Status", "Time", "Total",
+];
+
+/// Print one row per run, aligned into columns, followed by a row of total times and then any
+/// days that couldn't be run and answers that did not match the expected answer
+pub fn print_summary(runs: &[DayRun]) {
+    let rows = runs
+        .iter()
+        .map(|run| {
+            let [part1, part2] = &run.parts;
+            if run.error.is_some() {
+                let error = Status::Error.to_string();
+                let mut row = [const { String::new() }; 9];
+                row[0] = run.day.to_string();
+                row[3] = error.clone();
+                row[6] = error;
+                return row;
+            }
+            [
+                run.day.to_string(),
+                format_duration(run.parse_time),
+                part1.answer.to_string(),
+                part1.status.to_string(),
+                format_duration(part1.time),
+                part2.answer.to_string(),
+                part2.status.to_string(),
+                format_duration(part2.time),
+                format_duration(run.total_time()),
+            ]
+        })
+        .collect::<Vec<_>>();
+
+    let sum = |f: fn(&DayRun) -> Duration| format_duration(runs.iter().map(f).sum());
+    let totals = [
+        "Total".to_string(),
+        sum(|run| run.parse_time),
+        String::new(),
+        String::new(),
+        sum(|run| run.parts[0].time),
+        String::new(),
+        String::new(),
+        sum(|run| run.parts[1].time),
+        sum(DayRun::total_time),
+    ];
+
+    print_table(HEADER, &rows, Some(&totals));
+
+    for run in runs {
+        if let Some(error) = &run.error {
+            println!();
+            println!("Day {}:", run.day);
+            println!("{error:?}");
+        }
+        for (i, part) in run.parts.iter().enumerate() {
+            if let Status::Fail { expected } = &part.status {
+                println!();
+                println!("Day {} Part {}:", run.day, i + 1);
+                print_diff(expected, &part.answer);
+            }
+        }
+    }
+}
+
+/// Print an expected and actual answer one above the other
+pub fn print_diff(expected: &Answer, actual: &Answer) {
+    println!("  expected: {expected}");
+    println!("  actual:   {actual}");
+}
+
+/// Print `rows` right-aligned under `header`, with an optional footer row set apart at the bottom
+pub fn print_table<const N: usize>(
+    header: [&str; N],
+    rows: &[[String; N]],
+    footer: Option<&[String; N]>,
+) {
+    let mut widths = header.map(|h| h.chars().count());
+    for row in rows.iter().chain(footer) {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let separator = widths
+        .iter()
+        .map(|w| "-".repeat(*w))
+        .collect::<Vec<_>>()
+        .join("-+-");
+
+    print_row(&header.map(String::from), &widths);
+    println!("{separator}");
+    for row in rows {
+        print_row(row, &widths);
+    }
+    if let Some(footer) = footer {
+        println!("{separator}");
+        print_row(footer, &widths);
+    }
+}
+
+fn print_row<const N: usize>(row: &[String; N], widths: &[usize; N]) {
+    let line = row
+        .iter()
+        .zip(widths)
+        .map(|(cell, width)| format!("{cell:>width$}"))
+        .collect::<Vec<_>>()
+        .join(" | ");
+    println!("{line}");
+}
+
+pub fn format_duration(d: Duration) -> String {
+    format!("{d:.2?}")
+}
@@ -0,0 +1,51 @@
+//! Runs every example input against its sidecar `.answers` file, one test per part
+//!
+//! The tests themselves are generated by `build.rs`, so adding `inputs/day_NN-example.txt` and
+//! `inputs/day_NN-example.answers` is all it takes to cover a new example.
+
+use std::{collections::BTreeMap, fs};
+
+use aoc_2025::{answer::Answer, params::Params, solutions};
+use serde::Deserialize;
+
+/// The contents of an `.answers` file
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Expected {
+    part1: Option<Answer>,
+    part2: Option<Answer>,
+    /// Parameters to override for this example, like `connections = 10`
+    #[serde(default)]
+    params: BTreeMap<String, usize>,
+}
+
+fn check(day: u32, input: &str, answers: &str, part: usize) {
+    let solution = solutions::get(day).unwrap_or_else(|| panic!("day {day} is not registered"));
+
+    let raw_input = fs::read_to_string(input).unwrap();
+    let expected = toml::from_str::<Expected>(&fs::read_to_string(answers).unwrap()).unwrap();
+
+    let overrides = expected.params.clone().into_iter().collect::<Vec<_>>();
+    for (name, _) in &overrides {
+        assert!(
+            solution.params.iter().any(|p| p.name == name),
+            "day {day} has no parameter named `{name}`"
+        );
+    }
+    let params = Params::with_overrides(solution.params, &overrides).unwrap();
+
+    let solver = solution
+        .solver(raw_input, &params)
+        .unwrap_or_else(|e| panic!("failed to parse `{input}`: {e}\n{}", e.excerpt()));
+
+    let (actual, expected) = match part {
+        1 => (solver.part1(), expected.part1),
+        2 => (solver.part2(), expected.part2),
+        _ => unreachable!("days only have two parts"),
+    };
+    let expected = expected.unwrap_or_else(|| panic!("no expected answer for part {part}"));
+
+    assert_eq!(actual, expected);
+}
+
+include!(concat!(env!("OUT_DIR"), "/examples.rs"));
@@ -0,0 +1,262 @@
+//! Checks that parse errors point at the offending line and column, both for [`ParseError`] itself
+//! and for malformed input to every day
+
+use aoc_2025::{params::Params, parse::ParseError, solutions};
+
+/// Parse `input` for `day`, expecting an error
+fn parse_error(day: u32, input: &str) -> ParseError {
+    let solution = solutions::get(day).unwrap_or_else(|| panic!("day {day} is not registered"));
+    let params = Params::defaults(solution.params);
+
+    match solution.solver(input.to_string(), &params) {
+        Ok(_) => panic!("day {day} parsed malformed input: {input:?}"),
+        Err(e) => e,
+    }
+}
+
+/// Check that parsing `input` for `day` fails at `line` and `column` with `message`
+#[track_caller]
+fn assert_error(day: u32, input: &str, line: usize, column: usize, message: &str) {
+    let error = parse_error(day, input);
+    assert_eq!(
+        error.to_string(),
+        format!("line {line}, column {column}: {message}"),
+        "\n{}",
+        error.excerpt()
+    );
+}
+
+#[test]
+fn span_inside_line() {
+    let line = "L68 R30";
+    let error = ParseError::new(line, &line[4..], "bad instruction");
+
+    assert_eq!(error.to_string(), "line 1, column 5: bad instruction");
+    assert_eq!(
+        error.excerpt(),
+        "  |\n1 | L68 R30\n  |     ^^^ bad instruction"
+    );
+}
+
+#[test]
+fn span_outside_line_covers_whole_line() {
+    let line = "L68";
+    let elsewhere = String::from("L68");
+    let error = ParseError::new(line, &elsewhere, "bad instruction");
+
+    assert_eq!(error.to_string(), "line 1, column 1: bad instruction");
+    assert_eq!(error.excerpt(), "  |\n1 | L68\n  | ^^^ bad instruction");
+}
+
+#[test]
+fn span_counts_chars_not_bytes() {
+    let line = "é→x€";
+    let x = line.find('x').unwrap();
+    let error = ParseError::new(line, &line[x..], "unexpected chars");
+
+    assert_eq!(error.to_string(), "line 1, column 3: unexpected chars");
+    assert_eq!(error.excerpt(), "  |\n1 | é→x€\n  |   ^^ unexpected chars");
+}
+
+#[test]
+fn empty_span_at_end_of_line() {
+    let line = "[.##.] (3)";
+    let error = ParseError::new(line, &line[line.len()..], "expected joltage requirements");
+
+    assert_eq!(
+        error.to_string(),
+        "line 1, column 11: expected joltage requirements"
+    );
+    assert_eq!(
+        error.excerpt(),
+        "  |\n1 | [.##.] (3)\n  |           ^ expected joltage requirements"
+    );
+}
+
+#[test]
+fn excerpt_gutter_fits_line_number() {
+    let error = ParseError::line("x", "bad line").offset_lines(11);
+
+    assert_eq!(error.to_string(), "line 12, column 1: bad line");
+    assert_eq!(error.excerpt(), "   |\n12 | x\n   | ^ bad line");
+}
+
+#[test]
+fn end_of_input_ignores_trailing_blank_lines() {
+    let error = ParseError::end_of_input("3-5\n10-14\n\n\n", "expected ingredients");
+
+    assert_eq!(error.to_string(), "line 2, column 6: expected ingredients");
+}
+
+#[test]
+fn day_01() {
+    assert_error(1, "L68\nX30\n", 2, 1, "invalid direction: `X`");
+    assert_error(
+        1,
+        "L68\nR30\nL3x\n",
+        3,
+        2,
+        "invalid number `3x`: invalid digit found in string",
+    );
+}
+
+#[test]
+fn day_02() {
+    assert_error(
+        2,
+        "11-22,95x115\n",
+        1,
+        7,
+        "expected a range like `11-22`, found `95x115`",
+    );
+}
+
+#[test]
+fn day_03() {
+    assert_error(
+        3,
+        "987654321111111\n81119a\n",
+        2,
+        6,
+        "invalid joltage `a`, expected 1-9",
+    );
+}
+
+#[test]
+fn day_04() {
+    assert_error(4, "..@@\n@.x.\n", 2, 3, "unexpected char: `x`");
+    assert_error(4, "..@@\n@.@\n", 2, 1, "expected 4 tiles, found 3");
+}
+
+#[test]
+fn day_05() {
+    assert_error(5, "3-5\n10x14\n\n1\n", 2, 1, "expected a range like `3-5`");
+    assert_error(
+        5,
+        "3-5\n10-14\n\n1\nx5\n",
+        5,
+        1,
+        "invalid number `x5`: invalid digit found in string",
+    );
+    assert_error(
+        5,
+        "3-5\n10-14\n",
+        2,
+        6,
+        "expected a blank line followed by ingredients",
+    );
+}
+
+#[test]
+fn day_06() {
+    assert_error(6, "12 3\n4x 5\n*  +\n", 2, 2, "expected a digit or a space");
+    // blank lines are skipped, but still counted
+    assert_error(
+        6,
+        "12 3\n\n4x 5\n*  +\n",
+        3,
+        2,
+        "expected a digit or a space",
+    );
+    assert_error(
+        6,
+        "1  \n+  \n",
+        2,
+        3,
+        "expected a single blank column between problems",
+    );
+    assert_error(
+        6,
+        "  \n+ \n",
+        2,
+        2,
+        "expected a single blank column between problems",
+    );
+    assert_error(
+        6,
+        "1 2\n+  \n",
+        2,
+        3,
+        "expected an operator in the first column of the problem",
+    );
+    assert_error(
+        6,
+        "12 3\n 4  \n*  +\n",
+        2,
+        4,
+        "expected one number in each row of the problem",
+    );
+    assert_error(
+        6,
+        "123456789012345678901\n+                    \n",
+        1,
+        1,
+        "invalid number `123456789012345678901`: number too large to fit in target type",
+    );
+}
+
+#[test]
+fn day_07() {
+    assert_error(7, "..S..\n.....\n..^.x\n", 3, 5, "unexpected char: `x`");
+    assert_error(
+        7,
+        "..S..\n.....\n^....\n",
+        3,
+        1,
+        "splitter on the edge of the manifold",
+    );
+}
+
+#[test]
+fn day_08() {
+    assert_error(
+        8,
+        "162,817,812\n57,618,x\n",
+        2,
+        8,
+        "invalid number `x`: invalid digit found in string",
+    );
+    assert_error(
+        8,
+        "162,817,812\n",
+        1,
+        12,
+        "expected at least two junction boxes",
+    );
+}
+
+#[test]
+fn day_09() {
+    assert_error(
+        9,
+        "7,1\n11,1\n11,7\n9,5\n",
+        4,
+        1,
+        "not in the same row or column as the previous red tile",
+    );
+    assert_error(
+        9,
+        "7,1\n11,1\n11,7\n",
+        1,
+        1,
+        "not in the same row or column as the last red tile",
+    );
+}
+
+#[test]
+fn day_10() {
+    assert_error(
+        10,
+        "[.##.] (3) (1,3) (2) (2,3) (0,2) (0,1) {3,5,4,7}\n[#.] (0) (5) {1,1}\n",
+        2,
+        11,
+        "light 5 is outside the diagram of 2 lights",
+    );
+    assert_error(
+        10,
+        "[#] (0,0) {2}\n",
+        1,
+        8,
+        "light 0 is already toggled by this button",
+    );
+}